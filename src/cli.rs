@@ -0,0 +1,930 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::{
+    csv::{
+        export_tournament, write_fitness, write_moran_runs, write_population, write_site_counts,
+        write_sweep,
+    },
+    ecology::{run_ecology, EcologyConfig, DEFAULT_GENERATIONS},
+    genetic::{export_strategy, save_checkpoint, Checkpoint, GeneticConfig, CHECKPOINT_EXTENSION},
+    interpolation::parse_interpolation,
+    interpretation::Interpretation,
+    length::MatchLength,
+    moran::{run_moran, MoranConfig},
+    noise::{Noise, NoiseKind},
+    payoff::Payoff,
+    registry::{self, registry, Family},
+    run_competition,
+    session::{save_session, Session, SESSION_EXTENSION},
+    spatial::{Lattice, Neighbourhood, SpatialConfig},
+    stats::calculate_stats,
+    strategies::{evolved::EVOLVED_DIR, memory_one::MemoryOne},
+    sweep::{run_sweep, SweepConfig, SweepParameter},
+    tournament::TournamentConfig,
+    MatchupResult, NUM_ROUNDS,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "smooth_criminal",
+    version,
+    about = "Iterated prisoner's dilemma with continuous moves"
+)]
+pub struct Cli {
+    /// Opens the viewer when left out
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run a tournament without a window, print the rankings and write the results to disk
+    Run {
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        /// Directory the results are written to
+        #[arg(short, long, default_value = "output")]
+        output: PathBuf,
+
+        /// Also write every move of every matchup (one row per round)
+        #[arg(long)]
+        history: bool,
+    },
+    /// Open a saved session in the viewer, or run a tournament and open the viewer
+    View {
+        /// Session file written by `run` or the viewer's "Save..." button, shown as it was saved
+        #[arg(conflicts_with_all = ["TournamentArgs", "EcologyArgs"])]
+        file: Option<PathBuf>,
+
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        /// Settings of the population panel
+        #[command(flatten)]
+        ecology: EcologyArgs,
+    },
+    /// Run a tournament, then evolve a population of its strategies with replicator dynamics
+    Ecology {
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        #[command(flatten)]
+        ecology: EcologyArgs,
+
+        /// Directory the population shares are written to
+        #[arg(short, long, default_value = "output")]
+        output: PathBuf,
+    },
+    /// Run a Moran process, a finite population where individuals reproduce proportionally to their fitness
+    Moran {
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        #[command(flatten)]
+        moran: MoranArgs,
+
+        /// Directory the outcome of every run is written to
+        #[arg(short, long, default_value = "output")]
+        output: PathBuf,
+    },
+    /// Play a spatial tournament on a lattice, where every site imitates its best scoring neighbour
+    Spatial {
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        #[command(flatten)]
+        spatial: SpatialArgs,
+
+        /// Run without a window and write the number of sites per strategy to disk
+        #[arg(long)]
+        headless: bool,
+
+        /// Generations played when headless, stops early once the lattice no longer changes
+        #[arg(long, default_value_t = 100)]
+        lattice_generations: usize,
+
+        /// Directory the site counts are written to when headless
+        #[arg(short, long, default_value = "output")]
+        output: PathBuf,
+    },
+    /// Evolve a memory-n strategy with a genetic algorithm, rated against the entered strategies
+    Evolve {
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        #[command(flatten)]
+        genetic: GeneticArgs,
+
+        /// Continue from a checkpoint, whose settings replace the ones given here except for `--ga-generations`
+        #[arg(long)]
+        resume: Option<PathBuf>,
+
+        /// Write a checkpoint every this many generations (and after the last one)
+        #[arg(long, default_value_t = 10)]
+        checkpoint_every: usize,
+
+        /// Name the best individual is exported under
+        #[arg(long, default_value = "Evolved")]
+        name: String,
+
+        /// Directory the best individual is exported to, strategies in it are entered like built-in ones
+        #[arg(long, default_value = EVOLVED_DIR)]
+        export: PathBuf,
+
+        /// Directory the fitness history and checkpoints are written to
+        #[arg(short, long, default_value = "output")]
+        output: PathBuf,
+    },
+    /// Score a grid of memory-one strategies against the entered strategies, varying two of their five moves
+    Sweep {
+        #[command(flatten)]
+        tournament: TournamentArgs,
+
+        #[command(flatten)]
+        sweep: SweepArgs,
+
+        /// Print the scores and write them to disk instead of showing a heatmap
+        #[arg(long)]
+        headless: bool,
+
+        /// Directory the scores are written to when headless
+        #[arg(short, long, default_value = "output")]
+        output: PathBuf,
+    },
+    /// List the names of every available strategy
+    ListStrategies {
+        /// Only list classic, continuous or custom strategies
+        #[arg(long, value_parser = Family::parse)]
+        family: Option<Family>,
+
+        /// Also print the id, family, memory and whether each strategy is stochastic
+        #[arg(short, long)]
+        long: bool,
+
+        /// List the strategies that can be built from a spec such as npavlov(n=16) instead, with their parameters
+        #[arg(long)]
+        templates: bool,
+    },
+    /// Describe a strategy, given by id, name or spec
+    Info { strategy: String },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TournamentArgs {
+    #[arg(long, default_value_t = NUM_ROUNDS)]
+    pub rounds: usize,
+
+    #[arg(long, default_value_t = 1)]
+    pub repetitions: usize,
+
+    /// Point values as R,P,T,S
+    #[arg(long, value_parser = parse_payoff, default_value = "2,1,3,0")]
+    pub payoff: Payoff,
+
+    /// bilinear, power(e), sigmoid(k, m), threshold(t) or expected
+    #[arg(long, default_value = "bilinear")]
+    pub interpolation: String,
+
+    /// How classic strategies read continuous moves: stochastic, threshold, threshold(t) or hysteresis(low, high)
+    #[arg(long, value_parser = Interpretation::parse, default_value = "stochastic")]
+    pub interpretation: Interpretation,
+
+    /// Noise on executed moves: none, flip(p) or gaussian(sigma)
+    #[arg(long, value_parser = NoiseKind::parse, default_value = "none")]
+    pub action_noise: NoiseKind,
+
+    /// Noise on what each player sees of its opponent: none, flip(p) or gaussian(sigma)
+    #[arg(long, value_parser = NoiseKind::parse, default_value = "none")]
+    pub perception_noise: NoiseKind,
+
+    /// fixed, hidden (fixed but not told to the strategies) or geometric(w) with continuation chance w
+    #[arg(long, value_parser = MatchLength::parse, default_value = "fixed")]
+    pub match_length: MatchLength,
+
+    /// Discount factor of the discounted score, defaults to w for geometric matches
+    #[arg(long)]
+    pub discount: Option<f64>,
+
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Enter only this strategy, by id, name or spec such as "detente(comfort=0.5, trust=0.2)", can be repeated (defaults to every strategy)
+    #[arg(short, long = "strategy")]
+    pub strategies: Vec<String>,
+
+    /// Also enter the strategies listed in this file, one id, name or spec per line, # starts a comment
+    #[arg(long)]
+    pub strategy_file: Option<PathBuf>,
+
+    /// Skip matchups of a strategy against itself
+    #[arg(long)]
+    pub no_self_play: bool,
+
+    /// Tell strategies the id of their opponent before every match
+    #[arg(long)]
+    pub reveal_opponents: bool,
+
+    /// Let strategies keep what they learned from one repetition of a pairing to the next
+    #[arg(long)]
+    pub carry_over: bool,
+}
+
+impl TournamentArgs {
+    pub fn to_config(&self) -> Result<TournamentConfig, String> {
+        self.to_config_with(EcologyConfig::default())
+    }
+
+    /// `to_config` for the commands that run the ecological simulation on the tournament
+    pub fn to_config_with_ecology(
+        &self,
+        ecology: &EcologyArgs,
+    ) -> Result<TournamentConfig, String> {
+        self.to_config_with(ecology.to_config())
+    }
+
+    fn to_config_with(&self, ecology: EcologyConfig) -> Result<TournamentConfig, String> {
+        if let Err(e) = self.payoff.validate() {
+            eprintln!("warning: {}", e);
+        }
+
+        let config = TournamentConfig {
+            rounds: self.rounds,
+            repetitions: self.repetitions,
+            payoff: self.payoff,
+            interpolation: parse_interpolation(&self.interpolation)?,
+            interpretation: self.interpretation,
+            noise: Noise {
+                action: self.action_noise,
+                perception: self.perception_noise,
+            },
+            match_length: self.match_length,
+            discount: self.discount,
+            seed: self.seed,
+            included_strategies: self.included_strategies()?,
+            self_play: !self.no_self_play,
+            reveal_opponents: self.reveal_opponents,
+            carry_over: self.carry_over,
+            ecology,
+        };
+
+        config.validate()?;
+        let strategies = config.resolve_strategies()?;
+        if config.matchup_count(strategies.len()) == 0 {
+            return Err(String::from(
+                "no matchups to play, a single strategy needs self-play",
+            ));
+        }
+        config.ecology.initial_population(strategies.len())?;
+        Ok(config)
+    }
+
+    /// `--strategy` followed by the lines of `--strategy-file`, `None` when neither is given
+    fn included_strategies(&self) -> Result<Option<Vec<String>>, String> {
+        let mut strategies = self.strategies.clone();
+
+        if let Some(path) = &self.strategy_file {
+            let contents =
+                fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            strategies.extend(
+                contents
+                    .lines()
+                    .map(|line| line.split('#').next().unwrap_or_default().trim())
+                    .filter(|line| !line.is_empty())
+                    .map(String::from),
+            );
+        }
+
+        Ok((!strategies.is_empty()).then_some(strategies))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct EcologyArgs {
+    /// Generations of the ecological simulation
+    #[arg(long, default_value_t = DEFAULT_GENERATIONS)]
+    pub generations: usize,
+
+    /// Starting population share of every entered strategy, in order (defaults to equal shares)
+    #[arg(long, value_delimiter = ',')]
+    pub initial_shares: Vec<f64>,
+}
+
+impl EcologyArgs {
+    pub fn to_config(&self) -> EcologyConfig {
+        EcologyConfig {
+            generations: self.generations,
+            initial_shares: if self.initial_shares.is_empty() {
+                None
+            } else {
+                Some(self.initial_shares.clone())
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MoranArgs {
+    #[arg(long, default_value_t = 10)]
+    pub population: usize,
+
+    /// Chance that an offspring becomes a random entered strategy
+    #[arg(long, default_value_t = 0.0)]
+    pub mutation_rate: f64,
+
+    /// Number of independent runs
+    #[arg(long, default_value_t = 100)]
+    pub runs: usize,
+
+    /// Births after which a run that has not fixated is given up
+    #[arg(long, default_value_t = 10_000)]
+    pub max_steps: usize,
+
+    /// Starting number of individuals of every entered strategy, in order (defaults to an even spread)
+    #[arg(long, value_delimiter = ',')]
+    pub initial_counts: Vec<usize>,
+}
+
+impl MoranArgs {
+    pub fn to_config(&self) -> Result<MoranConfig, String> {
+        let config = MoranConfig {
+            population_size: self.population,
+            mutation_rate: self.mutation_rate,
+            runs: self.runs,
+            max_steps: self.max_steps,
+            initial_counts: if self.initial_counts.is_empty() {
+                None
+            } else {
+                Some(self.initial_counts.clone())
+            },
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SpatialArgs {
+    #[arg(long, default_value_t = 30)]
+    pub width: usize,
+
+    #[arg(long, default_value_t = 30)]
+    pub height: usize,
+
+    /// von-neumann (4 neighbours) or moore (8 neighbours)
+    #[arg(long, value_parser = Neighbourhood::parse, default_value = "von-neumann")]
+    pub neighbourhood: Neighbourhood,
+
+    /// Stop the lattice at its edges instead of wrapping around
+    #[arg(long)]
+    pub no_wrap: bool,
+}
+
+impl SpatialArgs {
+    pub fn to_config(&self) -> Result<SpatialConfig, String> {
+        let config = SpatialConfig {
+            width: self.width,
+            height: self.height,
+            neighbourhood: self.neighbourhood,
+            wrap: !self.no_wrap,
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GeneticArgs {
+    /// Number of past rounds the evolved strategy looks at
+    #[arg(long, default_value_t = 1)]
+    pub depth: usize,
+
+    #[arg(long, default_value_t = 40)]
+    pub population: usize,
+
+    /// Generations of the genetic algorithm [default: 50, or the checkpoint's when resuming]
+    #[arg(long)]
+    pub ga_generations: Option<usize>,
+
+    /// Chance that a single gene is mutated
+    #[arg(long, default_value_t = 0.1)]
+    pub mutation_rate: f64,
+
+    /// Standard deviation of a mutation
+    #[arg(long, default_value_t = 0.2)]
+    pub mutation_scale: f64,
+
+    /// Best individuals copied unchanged into the next generation
+    #[arg(long, default_value_t = 2)]
+    pub elite: usize,
+
+    /// Individuals competing for every parent slot
+    #[arg(long, default_value_t = 3)]
+    pub tournament_size: usize,
+}
+
+impl GeneticArgs {
+    pub fn to_config(&self) -> Result<GeneticConfig, String> {
+        let config = GeneticConfig {
+            depth: self.depth,
+            population_size: self.population,
+            generations: self
+                .ga_generations
+                .unwrap_or(GeneticConfig::default().generations),
+            mutation_rate: self.mutation_rate,
+            mutation_scale: self.mutation_scale,
+            elite: self.elite,
+            tournament_size: self.tournament_size,
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Moves of the swept memory-one strategy, named by my move then the opponent's (`cd`: I cooperated, they defected)
+#[derive(Debug, Clone, Args)]
+pub struct SweepArgs {
+    /// Parameter along the columns: opening, cc, cd, dc or dd
+    #[arg(long, value_parser = SweepParameter::parse, default_value = "cd")]
+    pub x: SweepParameter,
+
+    /// Parameter along the rows: opening, cc, cd, dc or dd
+    #[arg(long, value_parser = SweepParameter::parse, default_value = "dd")]
+    pub y: SweepParameter,
+
+    /// Values per parameter, evenly spaced from 0 to 1
+    #[arg(long, default_value_t = 11)]
+    pub steps: usize,
+
+    /// Fixed values of the parameters that are not swept, Tit for Tat by default
+    #[arg(long, default_value_t = 0.0)]
+    pub opening: f64,
+
+    #[arg(long, default_value_t = 0.0)]
+    pub cc: f64,
+
+    #[arg(long, default_value_t = 0.0)]
+    pub dc: f64,
+
+    #[arg(long, default_value_t = 1.0)]
+    pub cd: f64,
+
+    #[arg(long, default_value_t = 1.0)]
+    pub dd: f64,
+}
+
+impl SweepArgs {
+    pub fn to_config(&self) -> Result<SweepConfig, String> {
+        let config = SweepConfig {
+            x: self.x,
+            y: self.y,
+            steps: self.steps,
+            base: MemoryOne::new(self.opening, self.cc, self.dc, self.cd, self.dd),
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+fn parse_payoff(s: &str) -> Result<Payoff, String> {
+    let values = s
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number", v))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match values[..] {
+        [r, p, t, s] => Ok(Payoff::new(r, p, t, s)),
+        _ => Err(String::from("expected four values: R,P,T,S")),
+    }
+}
+
+pub fn list_strategies(family: Option<Family>, long: bool, templates: bool) {
+    if templates {
+        for template in registry::templates() {
+            if template.strategies.is_empty() {
+                println!("{}", template.id);
+            } else {
+                println!("{}({})", template.id, template.strategies);
+            }
+            for parameter in &template.parameters {
+                println!(
+                    "    {:<14} default {:<6} {}{} to {}",
+                    parameter.name,
+                    parameter.default,
+                    if parameter.integer {
+                        "whole number, "
+                    } else {
+                        ""
+                    },
+                    parameter.range.start(),
+                    parameter.range.end()
+                );
+            }
+        }
+        return;
+    }
+
+    let infos = registry()
+        .into_iter()
+        .map(|registered| registered.info)
+        .filter(|info| family.is_none_or(|family| info.family == family));
+
+    if !long {
+        for info in infos {
+            println!("{}", info.name);
+        }
+        return;
+    }
+
+    println!(
+        "{:<32}  {:<36}  {:<10}  {:<11}  Stochastic",
+        "Id", "Name", "Family", "Memory"
+    );
+    for info in infos {
+        println!(
+            "{:<32}  {:<36}  {:<10}  {:<11}  {}",
+            info.id,
+            info.name,
+            info.family.to_string(),
+            info.memory.to_string(),
+            if info.stochastic { "yes" } else { "no" }
+        );
+    }
+}
+
+/// Prints the first error of every strategy file that failed to load, or script that failed to play
+pub fn report_strategy_errors() {
+    for (source, error) in registry::errors() {
+        eprintln!("warning: {}: {}", source, error);
+    }
+}
+
+pub fn strategy_info(strategy: &str) -> Result<(), String> {
+    let info = if registry::is_spec(strategy) {
+        registry::build(strategy)?.info
+    } else {
+        registry::find(strategy)
+            .ok_or_else(|| format!("unknown strategy '{}'", strategy))?
+            .info
+    };
+
+    if info.id == info.name {
+        println!("{}", info.name);
+    } else {
+        println!("{} ({})", info.name, info.id);
+    }
+    if !info.description.is_empty() {
+        println!("{}", info.description);
+    }
+    println!();
+    println!("Family:     {}", info.family);
+    println!(
+        "Author:     {}",
+        if info.author.is_empty() {
+            "unknown"
+        } else {
+            &info.author
+        }
+    );
+    println!("Memory:     {}", info.memory);
+    println!("Stochastic: {}", if info.stochastic { "yes" } else { "no" });
+    if !info.parameters.is_empty() {
+        println!("Parameters: {}", info.parameter_list());
+    }
+    Ok(())
+}
+
+pub async fn run(
+    config: TournamentConfig,
+    output: PathBuf,
+    include_history: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.with_resolved_seed();
+    let strategies = config.resolve_strategies()?;
+    let names: Vec<_> = strategies.iter().map(|(name, _)| name.clone()).collect();
+
+    let results = run_competition(strategies, &config).await?;
+    let stats = calculate_stats(&results, &config, names.len()).await;
+
+    print_rankings(&names, &results);
+    println!("\nSeed: {}", config.seed.unwrap_or_default());
+
+    let mut written =
+        export_tournament(&output, &config, &names, &results, &stats, include_history).await?;
+
+    let session_path = output.join(format!("session.{}", SESSION_EXTENSION));
+    save_session(&session_path, &Session::new(config, names, results, &stats)).await?;
+    written.push(session_path);
+
+    println!();
+    for path in written {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+pub async fn ecology(
+    config: TournamentConfig,
+    output: PathBuf,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.with_resolved_seed();
+    let strategies = config.resolve_strategies()?;
+    let names: Vec<_> = strategies.iter().map(|(name, _)| name.clone()).collect();
+
+    let results = run_competition(strategies, &config).await?;
+    let population = run_ecology(&results, &config, names.len())?;
+
+    let mut survivors: Vec<_> = names.iter().zip(population.final_shares()).collect();
+    survivors.sort_by(|a, b| b.1.total_cmp(a.1));
+
+    let width = names
+        .iter()
+        .map(|n| n.len())
+        .max()
+        .unwrap_or(0)
+        .max("Strategy".len());
+
+    println!(
+        "Population after {} generations\n",
+        population.shares.len() - 1
+    );
+    println!("{:>4}  {:<width$}  {:>10}", "Rank", "Strategy", "Share");
+    for (rank, (name, share)) in survivors.iter().enumerate() {
+        println!("{:>4}  {:<width$}  {:>10.6}", rank + 1, name, share);
+    }
+    println!("\nSeed: {}", config.seed.unwrap_or_default());
+
+    let path = output.join("population.csv");
+    write_population(&path, &names, &population).await?;
+    println!("\nWrote {}", path.display());
+
+    Ok(())
+}
+
+pub async fn moran(
+    config: TournamentConfig,
+    moran: MoranConfig,
+    output: PathBuf,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.with_resolved_seed();
+    let strategies = config.resolve_strategies()?;
+    let names: Vec<_> = strategies.iter().map(|(name, _)| name.clone()).collect();
+
+    let result = run_moran(strategies, &config, &moran).await?;
+
+    let mut rankings: Vec<_> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            (
+                name,
+                result.fixation_probability(i),
+                result.mean_fixation_time(i),
+            )
+        })
+        .collect();
+    rankings.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let width = names
+        .iter()
+        .map(|n| n.len())
+        .max()
+        .unwrap_or(0)
+        .max("Strategy".len());
+
+    println!(
+        "{} runs, population of {}, mutation rate {}\n",
+        moran.runs, moran.population_size, moran.mutation_rate
+    );
+    println!(
+        "{:<width$}  {:>11}  {:>14}",
+        "Strategy", "Fixation", "Mean fixation"
+    );
+    for (name, probability, time) in rankings {
+        let time = if time.is_finite() {
+            format!("{:.1}", time)
+        } else {
+            String::from("-")
+        };
+        println!("{:<width$}  {:>11.3}  {:>14}", name, probability, time);
+    }
+    if result.unfixed() > 0 {
+        println!(
+            "\n{} runs did not fixate within {} births",
+            result.unfixed(),
+            moran.max_steps
+        );
+    }
+    println!("\nSeed: {}", config.seed.unwrap_or_default());
+
+    let path = output.join("moran.csv");
+    write_moran_runs(&path, &names, &result).await?;
+    println!("\nWrote {}", path.display());
+
+    Ok(())
+}
+
+pub async fn spatial(
+    config: TournamentConfig,
+    spatial: SpatialConfig,
+    generations: usize,
+    output: PathBuf,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let strategies = config.resolve_strategies()?;
+    let names: Vec<_> = strategies.iter().map(|(name, _)| name.clone()).collect();
+
+    let mut lattice = Lattice::random(&spatial, names.len(), &config);
+    let mut counts = vec![lattice.counts(names.len())];
+
+    for _ in 0..generations {
+        let next = lattice.step(&strategies, &config, &spatial);
+        let stable = next.sites == lattice.sites;
+
+        lattice = next;
+        counts.push(lattice.counts(names.len()));
+
+        if stable {
+            println!(
+                "The lattice stopped changing after {} generations\n",
+                lattice.generation
+            );
+            break;
+        }
+    }
+
+    let mut rankings: Vec<_> = names.iter().zip(counts.last().unwrap()).collect();
+    rankings.sort_by(|a, b| b.1.cmp(a.1));
+
+    let width = names
+        .iter()
+        .map(|n| n.len())
+        .max()
+        .unwrap_or(0)
+        .max("Strategy".len());
+
+    println!("{:<width$}  {:>6}", "Strategy", "Sites");
+    for (name, count) in rankings.iter().filter(|(_, &count)| count > 0) {
+        println!("{:<width$}  {:>6}", name, count);
+    }
+    println!("\nSeed: {}", config.seed.unwrap_or_default());
+
+    let path = output.join("spatial.csv");
+    write_site_counts(&path, &names, &counts).await?;
+    println!("\nWrote {}", path.display());
+
+    Ok(())
+}
+
+pub async fn evolve(
+    mut checkpoint: Checkpoint,
+    checkpoint_every: usize,
+    name: String,
+    export: PathBuf,
+    output: PathBuf,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let opponents = checkpoint.tournament.resolve_strategies()?;
+    let checkpoint_path = output.join(format!("checkpoint.{}", CHECKPOINT_EXTENSION));
+
+    println!(
+        "Evolving memory-{} strategies against {} opponents\n",
+        checkpoint.config.depth,
+        opponents.len()
+    );
+    println!("{:>10}  {:>10}  {:>10}", "Generation", "Best", "Mean");
+
+    while !checkpoint.is_finished() {
+        let stats = checkpoint.evolve(&opponents).await?;
+        println!(
+            "{:>10}  {:>10.4}  {:>10.4}",
+            checkpoint.generation, stats.best, stats.mean
+        );
+
+        if checkpoint_every > 0 && checkpoint.generation.is_multiple_of(checkpoint_every) {
+            save_checkpoint(&checkpoint_path, &checkpoint).await?;
+        }
+    }
+    println!("\nSeed: {}", checkpoint.tournament.seed.unwrap_or_default());
+
+    save_checkpoint(&checkpoint_path, &checkpoint).await?;
+    let fitness_path = output.join("fitness.csv");
+    write_fitness(&fitness_path, &checkpoint.history).await?;
+
+    println!();
+    if let Some((best, fitness)) = &checkpoint.best {
+        println!(
+            "Best individual ({:.4} points per round):\n{}",
+            fitness,
+            best.to_spec(&name)
+        );
+        let path = export_strategy(&export, &name, best).await?;
+        println!("Wrote {}", path.display());
+    }
+    println!("Wrote {}", fitness_path.display());
+    println!("Wrote {}", checkpoint_path.display());
+
+    Ok(())
+}
+
+pub async fn sweep(
+    config: TournamentConfig,
+    sweep: SweepConfig,
+    output: PathBuf,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let strategies = config.resolve_strategies()?;
+    let num_strategies = strategies.len();
+    let result = run_sweep(strategies, &config, &sweep).await?;
+
+    let (x, y) = (sweep.x.name(), sweep.y.name());
+    println!(
+        "Points per round against {} strategies, {} along the columns and {} along the rows\n",
+        num_strategies, x, y
+    );
+
+    print!("{:>8}", format!("{}\\{}", y, x));
+    for value in &result.x_values {
+        print!("  {:>6.2}", value);
+    }
+    println!();
+    for (row, value) in result.y_values.iter().enumerate() {
+        print!("{:>8.2}", value);
+        for col in 0..result.x_values.len() {
+            print!("  {:>6.3}", result.score(row, col));
+        }
+        println!();
+    }
+
+    if let Some((row, col)) = result.best() {
+        println!(
+            "\nBest: {} = {}, {} = {} with {:.4}",
+            x,
+            result.x_values[col],
+            y,
+            result.y_values[row],
+            result.score(row, col)
+        );
+    }
+    println!("\nSeed: {}", config.seed.unwrap_or_default());
+
+    let path = output.join("sweep.csv");
+    write_sweep(&path, &sweep, &result).await?;
+    println!("\nWrote {}", path.display());
+
+    Ok(())
+}
+
+fn print_rankings(names: &[String], results: &[MatchupResult]) {
+    let mut rankings: Vec<_> = names
+        .iter()
+        .map(|name| {
+            let played: Vec<_> = results.iter().filter(|r| &r.first_name == name).collect();
+            let n = played.len().max(1) as f64;
+
+            let score = played
+                .iter()
+                .map(|r| r.overall_result.0 / r.mean_rounds())
+                .sum::<f64>()
+                / n;
+            let difference = played
+                .iter()
+                .map(|r| (r.overall_result.0 - r.overall_result.1) / r.mean_rounds())
+                .sum::<f64>()
+                / n;
+
+            (name, score, difference)
+        })
+        .collect();
+
+    rankings.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let width = names
+        .iter()
+        .map(|n| n.len())
+        .max()
+        .unwrap_or(0)
+        .max("Strategy".len());
+
+    println!(
+        "{:>4}  {:<width$}  {:>16}  {:>16}",
+        "Rank", "Strategy", "Points per round", "Point diff/round"
+    );
+    for (rank, (name, score, difference)) in rankings.iter().enumerate() {
+        println!(
+            "{:>4}  {:<width$}  {:>16.4}  {:>16.4}",
+            rank + 1,
+            name,
+            score,
+            difference
+        );
+    }
+}
@@ -2,22 +2,22 @@ use iced::Color;
 
 pub const RED: Color = Color {
     r: 1.0,
-    g: 0.1960784314,
-    b: 0.1960784314,
+    g: 50.0 / 255.0,
+    b: 50.0 / 255.0,
     a: 1.0,
 };
 
 pub const BLUE: Color = Color {
-    r: 0.1960784314,
-    g: 0.1960784314,
+    r: 50.0 / 255.0,
+    g: 50.0 / 255.0,
     b: 1.0,
     a: 1.0,
 };
 
 pub const YELLOW: Color = Color {
-    r: 0.9882352941,
+    r: 252.0 / 255.0,
     g: 1.0,
-    b: 0.6196078431,
+    b: 158.0 / 255.0,
     a: 1.0,
 };
 
@@ -35,4 +35,32 @@ pub fn blend_colors(first: Color, second: Color, a: f32) -> Color {
         first.g * x + second.g * a,
         first.b * x + second.b * a,
     )
-}
\ No newline at end of file
+}
+
+/// One distinct color per strategy, used wherever strategies need to be told apart rather than ranked
+pub fn strategy_palette(n: usize) -> Vec<Color> {
+    (0..n)
+        .map(|i| {
+            // golden angle steps keep neighbouring strategies far apart on the hue circle
+            let hue = (i as f32 * 137.508) % 360.0;
+            let value = if i % 2 == 0 { 0.95 } else { 0.75 };
+            from_hsv(hue, 0.65, value)
+        })
+        .collect()
+}
+
+fn from_hsv(hue: f32, saturation: f32, value: f32) -> Color {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - chroma;
+
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Color::from_rgb(r + m, g + m, b + m)
+}
@@ -0,0 +1,209 @@
+// Checks that every continuous strategy plays like its classic counterpart when both players only play pure moves,
+// by feeding both the same random histories many times over. A deterministic classic strategy has to be matched move
+// for move once the continuous moves are rounded, a stochastic one has to be matched in the spread of its moves round by round
+
+use std::sync::Arc;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    interpolation::Bilinear, interpretation::Interpretation, payoff::Payoff,
+    strategies::continuous, tournament::find_strategy, Context, GameHistory, GameMove, Strategy,
+    COOPERATE, DEFECT,
+};
+
+/// Buckets the moves of stochastic strategies are sorted into before their spreads are compared
+const BINS: usize = 10;
+
+/// Histories both strategies are fed and how closely they have to agree
+#[derive(Debug, Clone)]
+pub struct ConformanceConfig {
+    pub rounds: usize,
+    pub histories: usize,
+    /// Replays of every history, stochastic strategies are compared by the spread of their moves
+    pub samples: usize,
+    /// Largest gap between both spreads that still conforms, deterministic strategies allow none
+    pub tolerance: f64,
+    pub seed: u64,
+}
+
+/// How far a continuous strategy strays from its classic counterpart
+#[derive(Debug, Clone)]
+pub struct Conformance {
+    pub classic: String,
+    pub continuous: String,
+    /// Whether the classic strategy played every history the same way in every sample
+    pub deterministic: bool,
+    /// Largest gap over every round of every history: the share of rounded continuous moves that differ
+    /// from the classic move when it is deterministic, the total variation distance between both spreads otherwise
+    pub gap: f64,
+    /// History and round of the largest gap
+    pub worst: (usize, usize),
+}
+
+impl Default for ConformanceConfig {
+    fn default() -> Self {
+        ConformanceConfig {
+            rounds: 20,
+            histories: 10,
+            samples: 20000,
+            tolerance: 0.05,
+            seed: 0,
+        }
+    }
+}
+
+impl ConformanceConfig {
+    /// Random pure histories, each with its own chance of defecting for both players
+    fn histories(&self) -> Vec<GameHistory> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let to_move = |d: bool| if d { DEFECT } else { COOPERATE };
+
+        (0..self.histories)
+            .map(|_| {
+                let (mine, theirs) = (rng.gen::<f64>(), rng.gen::<f64>());
+                (0..self.rounds)
+                    .map(|_| GameMove(to_move(rng.gen_bool(mine)), to_move(rng.gen_bool(theirs))))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Moves of `strategy` in every round of `history` in every sample, when it is fed that history whatever it plays
+    fn play(&self, strategy: &dyn Strategy, history: &GameHistory, index: usize) -> Vec<Vec<f64>> {
+        (0..self.samples)
+            .map(|sample| {
+                let mut strategy = dyn_clone::clone_box(strategy);
+                let rng =
+                    StdRng::seed_from_u64(self.seed ^ ((index * self.samples + sample) as u64 + 1));
+                let mut ctx = Context::new(
+                    Payoff::default(),
+                    Arc::new(Bilinear),
+                    Interpretation::default(),
+                    Some(history.len()),
+                    rng,
+                );
+
+                let mut seen = GameHistory::new();
+                history
+                    .iter()
+                    .map(|round| {
+                        let next_move = strategy.next_move(seen.last().cloned(), &seen, &mut ctx);
+                        seen.push(round.clone());
+                        next_move
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Share of `samples` whose move in `round` falls in every bucket
+fn spread(samples: &[Vec<f64>], round: usize) -> [f64; BINS] {
+    let mut shares = [0.0; BINS];
+    for moves in samples {
+        let bin = (moves[round].clamp(COOPERATE, DEFECT) * BINS as f64) as usize;
+        shares[bin.min(BINS - 1)] += 1.0 / samples.len() as f64;
+    }
+    shares
+}
+
+/// Gap between the moves of both strategies in every round, see `Conformance::gap`
+fn gaps(expected: &[Vec<f64>], actual: &[Vec<f64>], deterministic: bool) -> Vec<f64> {
+    (0..expected[0].len())
+        .map(|round| {
+            if deterministic {
+                let differing = actual
+                    .iter()
+                    .filter(|moves| moves[round].round() != expected[0][round])
+                    .count();
+                differing as f64 / actual.len() as f64
+            } else {
+                let (e, a) = (spread(expected, round), spread(actual, round));
+                e.iter().zip(&a).map(|(e, a)| (e - a).abs()).sum::<f64>() / 2.0
+            }
+        })
+        .collect()
+}
+
+impl Conformance {
+    pub fn conforms(&self, tolerance: f64) -> bool {
+        if self.deterministic {
+            self.gap == 0.0
+        } else {
+            self.gap <= tolerance
+        }
+    }
+}
+
+/// Compares every pair of `continuous::counterparts`
+pub fn check_conformance(config: &ConformanceConfig) -> Result<Vec<Conformance>, String> {
+    let histories = config.histories();
+
+    continuous::counterparts()
+        .into_iter()
+        .map(|(classic_name, continuous_name)| {
+            let find = |name: &str| {
+                find_strategy(name).ok_or_else(|| format!("unknown strategy '{}'", name))
+            };
+            let (classic, continuous) = (find(classic_name)?, find(continuous_name)?);
+
+            let played: Vec<_> = histories
+                .iter()
+                .enumerate()
+                .map(|(i, history)| {
+                    let expected = config.play(classic.as_ref(), history, i);
+                    let actual = config.play(continuous.as_ref(), history, i);
+                    (expected, actual)
+                })
+                .collect();
+
+            let mut conformance = Conformance {
+                classic: classic_name.to_string(),
+                continuous: continuous_name.to_string(),
+                deterministic: played
+                    .iter()
+                    .all(|(expected, _)| expected.iter().all(|moves| moves == &expected[0])),
+                gap: 0.0,
+                worst: (0, 0),
+            };
+            for (i, (expected, actual)) in played.iter().enumerate() {
+                let gaps = gaps(expected, actual, conformance.deterministic);
+                for (round, gap) in gaps.into_iter().enumerate() {
+                    if gap > conformance.gap {
+                        conformance.gap = gap;
+                        conformance.worst = (i, round);
+                    }
+                }
+            }
+            Ok(conformance)
+        })
+        .collect()
+}
+
+#[test]
+fn every_continuous_strategy_conforms() {
+    // fewer samples than the default, so the spreads of stochastic strategies are allowed to differ more
+    let config = ConformanceConfig {
+        samples: 2000,
+        tolerance: 0.1,
+        ..Default::default()
+    };
+
+    for result in check_conformance(&config).unwrap() {
+        assert!(
+            result.conforms(config.tolerance),
+            "{} strays {:.3} from {} ({}) in round {} of history {}",
+            result.continuous,
+            result.gap,
+            result.classic,
+            if result.deterministic {
+                "move for move"
+            } else {
+                "in spread"
+            },
+            result.worst.1 + 1,
+            result.worst.0
+        );
+    }
+}
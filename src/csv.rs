@@ -1,29 +1,357 @@
-pub async fn write_raw_results_to_csv(
-    path: &str,
-    results: &Vec<(&'static str, &'static str, GameResult)>,
-) -> Result<(), Box<dyn Error>> {
-    let path = Path::new(path);
-    let dir = path.parent().ok_or("Invalid Path")?;
+use std::{
+    error::Error,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-    fs::create_dir_all(dir).await?;
+use ::csv::Writer;
+use indexmap::IndexMap;
+use tokio::fs;
+
+use crate::{
+    ecology::Population,
+    genetic::GenerationStats,
+    moran::MoranResult,
+    play_round,
+    stats::Stat,
+    sweep::{SweepConfig, SweepResult},
+    tournament::TournamentConfig,
+    GameHistory, GameMove, GameResult, MatchupResult,
+};
+
+pub type ExportResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Writes the summary, every stat table and optionally the full history into `dir`, returning the files written
+pub async fn export_tournament(
+    dir: &Path,
+    config: &TournamentConfig,
+    strategy_names: &[String],
+    results: &[MatchupResult],
+    stats: &IndexMap<String, Arc<Stat>>,
+    include_history: bool,
+) -> ExportResult<Vec<PathBuf>> {
+    let mut written = vec![];
+
+    let path = dir.join("summary.csv");
+    write_matchup_summary(&path, results).await?;
+    written.push(path);
+
+    if include_history {
+        let path = dir.join("history.csv");
+        write_history(&path, results, config).await?;
+        written.push(path);
+    }
+
+    for (name, stat) in stats {
+        let path = dir.join(format!("{}.csv", to_file_name(name)));
+        write_stat_table(&path, strategy_names, stat).await?;
+        written.push(path);
+
+        if config.repetitions > 1 {
+            let path = dir.join(format!("{}_spread.csv", to_file_name(name)));
+            write_stat_spread(&path, strategy_names, stat).await?;
+            written.push(path);
+        }
+    }
 
-    let mut wtr = Writer::from_path(path)?;
-    wtr.write_record(&[
+    Ok(written)
+}
+
+/// One row per matchup with the (repetition averaged) score of each side
+pub async fn write_matchup_summary(path: &Path, results: &[MatchupResult]) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+    wtr.write_record([
         "First Strategy",
         "Second Strategy",
         "First Score",
         "Second Score",
+        "Rounds",
+    ])?;
+
+    for result in results {
+        let GameResult(first_score, second_score) = result.overall_result;
+
+        wtr.write_record([
+            result.first_name.as_str(),
+            result.second_name.as_str(),
+            first_score.to_string().as_str(),
+            second_score.to_string().as_str(),
+            result.mean_rounds().to_string().as_str(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Long format, one row per round of every matchup
+/// Noisy tournaments also get the intended and perceived moves next to the executed ones
+pub async fn write_history(
+    path: &Path,
+    results: &[MatchupResult],
+    config: &TournamentConfig,
+) -> ExportResult<()> {
+    let noisy = !config.noise.is_none();
+
+    let mut wtr = create_writer(path).await?;
+    let mut header = vec![
+        "First Strategy",
+        "Second Strategy",
+        "Round",
+        "First Move",
+        "Second Move",
+        "First Points",
+        "Second Points",
+    ];
+    if noisy {
+        header.extend([
+            "First Intended",
+            "Second Intended",
+            "First Perceived",
+            "Second Perceived",
+        ]);
+    }
+    wtr.write_record(&header)?;
+
+    for result in results {
+        for (round, GameMove(first_move, second_move)) in result.history.iter().enumerate() {
+            let GameResult(first_points, second_points) = play_round(
+                *first_move,
+                *second_move,
+                &config.payoff,
+                config.interpolation.as_ref(),
+            );
+
+            let mut record = vec![
+                result.first_name.clone(),
+                result.second_name.clone(),
+                (round + 1).to_string(),
+                first_move.to_string(),
+                second_move.to_string(),
+                first_points.to_string(),
+                second_points.to_string(),
+            ];
+
+            if noisy {
+                let column = |moves: Option<&GameHistory>, first: bool| {
+                    moves
+                        .and_then(|moves| moves.get(round))
+                        .map(|m| if first { m.0 } else { m.1 }.to_string())
+                        .unwrap_or_default()
+                };
+                let intended = result.noise.as_ref().map(|n| &n.intended);
+                let perceived = result.noise.as_ref().map(|n| &n.perceived);
+
+                record.extend([
+                    column(intended, true),
+                    column(intended, false),
+                    column(perceived, true),
+                    column(perceived, false),
+                ]);
+            }
+
+            wtr.write_record(&record)?;
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// One row per strategy: its average followed by its value against every opponent
+pub async fn write_stat_table(
+    path: &Path,
+    strategy_names: &[String],
+    stat: &Stat,
+) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+
+    let header = ["Strategy", "Average"]
+        .into_iter()
+        .chain(strategy_names.iter().map(String::as_str));
+    wtr.write_record(header)?;
+
+    let n = strategy_names.len();
+    for (i, name) in strategy_names.iter().enumerate() {
+        let row = [name.to_string(), stat.strategy_averages[i].to_string()]
+            .into_iter()
+            .chain(stat.values[i * n..(i + 1) * n].iter().map(format_value));
+        wtr.write_record(row)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Long format, one row per played matchup with the mean, standard deviation and 95% confidence interval
+pub async fn write_stat_spread(
+    path: &Path,
+    strategy_names: &[String],
+    stat: &Stat,
+) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+    wtr.write_record([
+        "Strategy", "Opponent", "Mean", "Std Dev", "CI Low", "CI High",
     ])?;
 
-    for (first_name, second_name, GameResult(first_score, second_score)) in results {
-        wtr.write_record(&[
-            first_name,
-            second_name,
-            &first_score.to_string().as_str(),
-            &second_score.to_string().as_str(),
+    let n = strategy_names.len();
+    for (i, value) in stat.values.iter().enumerate() {
+        if !value.is_finite() {
+            continue;
+        }
+
+        let (low, high) = stat.interval(i);
+        wtr.write_record([
+            strategy_names[i / n].clone(),
+            strategy_names[i % n].clone(),
+            value.to_string(),
+            stat.std_devs[i].to_string(),
+            format_value(&low),
+            format_value(&high),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// One row per generation with the population share of every strategy
+pub async fn write_population(
+    path: &Path,
+    strategy_names: &[String],
+    population: &Population,
+) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+
+    let header = ["Generation"]
+        .into_iter()
+        .chain(strategy_names.iter().map(String::as_str));
+    wtr.write_record(header)?;
+
+    for (generation, shares) in population.shares.iter().enumerate() {
+        let row = [generation.to_string()]
+            .into_iter()
+            .chain(shares.iter().map(f64::to_string));
+        wtr.write_record(row)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// One row per run of a Moran process with the strategy that took over and the births it took
+pub async fn write_moran_runs(
+    path: &Path,
+    strategy_names: &[String],
+    result: &MoranResult,
+) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+    wtr.write_record(["Run", "Fixated", "Steps"])?;
+
+    for (i, run) in result.runs.iter().enumerate() {
+        wtr.write_record([
+            i.to_string(),
+            run.fixated
+                .map(|s| strategy_names[s].clone())
+                .unwrap_or_default(),
+            run.steps.to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// One row per generation of the genetic algorithm with the best and mean fitness
+pub async fn write_fitness(path: &Path, history: &[GenerationStats]) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+    wtr.write_record(["Generation", "Best", "Mean"])?;
+
+    for (generation, stats) in history.iter().enumerate() {
+        wtr.write_record([
+            generation.to_string(),
+            stats.best.to_string(),
+            stats.mean.to_string(),
         ])?;
     }
 
     wtr.flush()?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// One row per variant of a parameter sweep with the two swept values and its score
+pub async fn write_sweep(
+    path: &Path,
+    sweep: &SweepConfig,
+    result: &SweepResult,
+) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+    wtr.write_record([sweep.x.name(), sweep.y.name(), "Score"])?;
+
+    for (row, y) in result.y_values.iter().enumerate() {
+        for (col, x) in result.x_values.iter().enumerate() {
+            wtr.write_record([
+                x.to_string(),
+                y.to_string(),
+                format_value(&result.score(row, col)),
+            ])?;
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// One row per generation of a spatial tournament with the number of sites held by every strategy
+pub async fn write_site_counts(
+    path: &Path,
+    strategy_names: &[String],
+    counts: &[Vec<usize>],
+) -> ExportResult<()> {
+    let mut wtr = create_writer(path).await?;
+
+    let header = ["Generation"]
+        .into_iter()
+        .chain(strategy_names.iter().map(String::as_str));
+    wtr.write_record(header)?;
+
+    for (generation, counts) in counts.iter().enumerate() {
+        let row = [generation.to_string()]
+            .into_iter()
+            .chain(counts.iter().map(usize::to_string));
+        wtr.write_record(row)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+async fn create_writer(path: &Path) -> ExportResult<Writer<File>> {
+    let dir = path.parent().ok_or("Invalid Path")?;
+    fs::create_dir_all(dir).await?;
+
+    Ok(Writer::from_path(path)?)
+}
+
+/// Matchups that were not played are left empty rather than written as NaN
+fn format_value(v: &f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        String::new()
+    }
+}
+
+pub fn to_file_name(stat_name: &str) -> String {
+    stat_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{tournament::TournamentConfig, MatchupResult};
+
+pub const DEFAULT_GENERATIONS: usize = 1000;
+
+/// Settings of the ecological simulation that is run on the results of a tournament
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EcologyConfig {
+    pub generations: usize,
+    /// Starting share of every strategy in tournament order, `None` starts from equal shares
+    pub initial_shares: Option<Vec<f64>>,
+}
+
+/// Population share of every strategy, one entry per generation starting with the initial shares
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Population {
+    pub shares: Vec<Vec<f64>>,
+}
+
+impl Default for EcologyConfig {
+    fn default() -> Self {
+        EcologyConfig {
+            generations: DEFAULT_GENERATIONS,
+            initial_shares: None,
+        }
+    }
+}
+
+impl EcologyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(shares) = &self.initial_shares {
+            if shares.iter().any(|s| !s.is_finite() || *s < 0.0) {
+                return Err(String::from("initial shares must be non-negative numbers"));
+            }
+            if shares.iter().sum::<f64>() <= 0.0 {
+                return Err(String::from("initial shares must not all be zero"));
+            }
+        }
+        Ok(())
+    }
+
+    /// The initial shares for `n` strategies, normalised to sum to 1
+    pub fn initial_population(&self, n: usize) -> Result<Vec<f64>, String> {
+        match &self.initial_shares {
+            None => Ok(vec![1.0 / n as f64; n]),
+            Some(shares) if shares.len() != n => Err(format!(
+                "{} initial shares were given for {} strategies",
+                shares.len(),
+                n
+            )),
+            Some(shares) => {
+                let total = shares.iter().sum::<f64>();
+                Ok(shares.iter().map(|s| s / total).collect())
+            }
+        }
+    }
+}
+
+impl Population {
+    pub fn final_shares(&self) -> &[f64] {
+        self.shares.last().map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Points per round of every strategy (row) against every opponent (column), NaN for matchups that were not played
+pub fn payoff_matrix(results: &[MatchupResult], config: &TournamentConfig, n: usize) -> Vec<f64> {
+    (0..n * n)
+        .map(|i| {
+            config
+                .matchup_index(n, i / n, i % n)
+                .map_or(f64::NAN, |index| {
+                    let result = &results[index];
+                    result.overall_result.0 / result.mean_rounds()
+                })
+        })
+        .collect()
+}
+
+/// Discrete replicator dynamics: every generation a strategy's share grows with its fitness relative to the population
+/// Fitness is the share weighted payoff against every opponent it played, shifted up when the payoffs go negative
+pub fn simulate(matrix: &[f64], n: usize, config: &EcologyConfig) -> Result<Population, String> {
+    let mut shares = config.initial_population(n)?;
+
+    let lowest = matrix
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(f64::INFINITY, |a, &b| a.min(b));
+    let offset = if lowest < 0.0 { -lowest } else { 0.0 };
+
+    let mut population = Population {
+        shares: vec![shares.clone()],
+    };
+
+    for _ in 0..config.generations {
+        let fitness: Vec<_> = (0..n)
+            .map(|i| {
+                let row = &matrix[i * n..(i + 1) * n];
+                let (points, weight) = row
+                    .iter()
+                    .zip(&shares)
+                    .filter(|(payoff, _)| payoff.is_finite())
+                    .fold((0.0, 0.0), |(points, weight), (payoff, share)| {
+                        (points + share * (payoff + offset), weight + share)
+                    });
+
+                if weight > 0.0 {
+                    points / weight
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let mean_fitness = shares.iter().zip(&fitness).map(|(s, f)| s * f).sum::<f64>();
+        if mean_fitness <= 0.0 {
+            break;
+        }
+
+        shares = shares
+            .iter()
+            .zip(&fitness)
+            .map(|(s, f)| s * f / mean_fitness)
+            .collect();
+        population.shares.push(shares.clone());
+    }
+
+    Ok(population)
+}
+
+/// Runs the ecological simulation configured in `config` on the results of its tournament
+pub fn run_ecology(
+    results: &[MatchupResult],
+    config: &TournamentConfig,
+    n: usize,
+) -> Result<Population, String> {
+    simulate(&payoff_matrix(results, config, n), n, &config.ecology)
+}
@@ -1,30 +1,106 @@
+use std::sync::Arc;
+
 use dyn_clone::DynClone;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinError;
+
+use crate::{
+    interpolation::PayoffInterpolation, interpretation::Interpretation, noise::NoiseRecord,
+    payoff::Payoff, registry, tournament::TournamentConfig,
+};
 
 pub const COOPERATE: f64 = 0.0;
 pub const DEFECT: f64 = 1.0;
 pub const NUM_ROUNDS: usize = 1000;
 
-pub const R: f64 = 2.0;
-pub const P: f64 = 1.0;
-pub const T: f64 = 3.0;
-pub const S: f64 = 0.0;
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchupResult {
-    pub first_name: &'static str,
-    pub second_name: &'static str,
+    pub first_name: String,
+    pub second_name: String,
+    /// Mean of `results`
     pub overall_result: GameResult,
+    /// Executed moves, the ones that were scored
     pub history: GameHistory,
-
+    /// Intended and perceived moves, only kept when the tournament has engine noise
+    pub noise: Option<NoiseRecord>,
+    /// Total score of every repetition
+    pub results: Vec<GameResult>,
+    /// Length of every repetition, `history` is the first one
+    pub rounds: Vec<usize>,
+    /// Points per round of every repetition with later rounds discounted, empty without a discount factor
+    pub discounted_results: Vec<GameResult>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameResult(pub f64, pub f64);
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameMove(pub f64, pub f64);
 pub type GameHistory = Vec<GameMove>;
+pub type NamedStrategy = (String, Box<dyn Strategy>);
+
+/// Information about the match that strategies may consult when choosing a move
+/// Each player has its own context, strategies must draw all of their randomness from `rng` so matches can be replayed
+#[derive(Debug, Clone)]
+pub struct Context {
+    pub payoff: Payoff,
+    pub interpolation: Arc<dyn PayoffInterpolation>,
+    /// How strategies built on pure moves read continuous moves
+    pub interpretation: Interpretation,
+    /// Length of the match, `None` when it is hidden from the strategies
+    pub rounds: Option<usize>,
+    pub rng: StdRng,
+}
+
+impl Context {
+    pub fn new(
+        payoff: Payoff,
+        interpolation: Arc<dyn PayoffInterpolation>,
+        interpretation: Interpretation,
+        rounds: Option<usize>,
+        rng: StdRng,
+    ) -> Self {
+        Context {
+            payoff,
+            interpolation,
+            interpretation,
+            rounds,
+            rng,
+        }
+    }
+
+    /// Points this player got for a move of its history
+    pub fn points(&self, GameMove(mine, theirs): &GameMove) -> f64 {
+        self.interpolation.interpolate(&self.payoff, *mine, *theirs)
+    }
+}
+
+/// What a strategy is told before the first move of a match
+#[derive(Debug, Clone)]
+pub struct MatchInfo {
+    /// Id of the opponent, `None` unless the tournament reveals opponents
+    pub opponent: Option<String>,
+    /// Length of the match, `None` when it is hidden from the strategies
+    pub rounds: Option<usize>,
+    pub payoff: Payoff,
+}
 
 pub trait Strategy: DynClone + Send {
-    fn next_move(&mut self, last_move: Option<GameMove>, history: &GameHistory) -> f64;
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64;
+
+    /// Forgets everything from earlier matches, so that the same instance plays like a fresh clone
+    /// The engine plays fresh clones itself, this is for callers that keep an instance around
+    fn reset(&mut self) {}
+
+    /// Called before the first move of every match
+    fn on_match_start(&mut self, _info: &MatchInfo) {}
+
+    /// Called after the last move of every match, with the total points and history from this player's perspective
+    fn on_match_end(&mut self, _result: &GameResult, _history: &GameHistory, _ctx: &mut Context) {}
 }
 
 dyn_clone::clone_trait_object!(Strategy);
@@ -35,34 +111,76 @@ struct FunctionalStrategyImpl {
 }
 
 impl Strategy for FunctionalStrategyImpl {
-    fn next_move(&mut self, _last_move: Option<GameMove>, history: &GameHistory) -> f64 {
+    fn next_move(
+        &mut self,
+        _last_move: Option<GameMove>,
+        history: &GameHistory,
+        _ctx: &mut Context,
+    ) -> f64 {
         (self.strategy)(history)
     }
 }
 
+#[derive(Clone, Debug)]
+struct ContextualStrategyImpl {
+    strategy: fn(&GameHistory, &mut Context) -> f64,
+}
+
+impl Strategy for ContextualStrategyImpl {
+    fn next_move(
+        &mut self,
+        _last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        (self.strategy)(history, ctx)
+    }
+}
+
 impl GameMove {
     pub fn switch_perspectives(&self) -> GameMove {
         GameMove(self.1, self.0)
     }
 }
 
+impl MatchupResult {
+    /// Average length over all repetitions
+    pub fn mean_rounds(&self) -> f64 {
+        if self.rounds.is_empty() {
+            return self.history.len() as f64;
+        }
+        self.rounds.iter().sum::<usize>() as f64 / self.rounds.len() as f64
+    }
+}
+
+/// Plays every pairing of `strategies`, in the order `TournamentConfig::matchup_index` expects
+/// Fails when a matchup panicked, as the results that remain would no longer line up with their pairings
 pub async fn run_competition(
-    strategies: Vec<(&'static str, Box<dyn Strategy>)>,
-) -> Vec<MatchupResult> {
+    strategies: Vec<NamedStrategy>,
+    config: &TournamentConfig,
+) -> Result<Vec<MatchupResult>, JoinError> {
+    let config = Arc::new(config.clone());
     let mut tasks = vec![];
 
-    for (first_name, first_strategy) in strategies.iter() {
-        for (second_name, second_strategy) in strategies.iter() {
-            let first_name = *first_name;
-            let second_name = *second_name;
-            let mut first_strategy = dyn_clone::clone(&*first_strategy);
-            let mut second_strategy = dyn_clone::clone(&*second_strategy);
+    for (i, (first_name, first_strategy)) in strategies.iter().enumerate() {
+        for (j, (second_name, second_strategy)) in strategies.iter().enumerate() {
+            if i == j && !config.self_play {
+                continue;
+            }
+
+            let first_name = first_name.clone();
+            let second_name = second_name.clone();
+            let first_strategy = first_strategy.clone();
+            let second_strategy = second_strategy.clone();
+            let config = Arc::clone(&config);
 
             let task = tokio::spawn(async move {
-                (
+                play_matchup(
                     first_name,
+                    first_strategy,
                     second_name,
-                    play_strategies(&mut first_strategy, &mut second_strategy),
+                    second_strategy,
+                    &config,
                 )
             });
 
@@ -72,56 +190,197 @@ pub async fn run_competition(
 
     let mut results = vec![];
     for task in tasks {
-        if let Ok(result) = task.await {
-            results.push(MatchupResult {
-                first_name: result.0,
-                second_name: result.1,
-                overall_result: result.2.0,
-                history: result.2.1,
-            });
+        results.push(task.await?);
+    }
+
+    Ok(results)
+}
+
+/// Plays every repetition of a single pairing, keeping the history of the first repetition
+pub fn play_matchup(
+    first_name: String,
+    first_strategy: Box<dyn Strategy>,
+    second_name: String,
+    second_strategy: Box<dyn Strategy>,
+    config: &TournamentConfig,
+) -> MatchupResult {
+    let discount = config.discount_factor();
+
+    let mut results = vec![];
+    let mut discounted_results = vec![];
+    let mut rounds = vec![];
+    let mut first_history = None;
+
+    // fresh clones play every repetition, unless the tournament carries over and the same instances keep learning
+    let mut first = first_strategy.clone();
+    let mut second = second_strategy.clone();
+
+    for repetition in 0..config.repetitions {
+        if repetition > 0 && !config.carry_over {
+            first = first_strategy.clone();
+            second = second_strategy.clone();
+        }
+        let mut rng = config.matchup_rng(&first_name, &second_name, repetition);
+
+        let (result, history, noise) = play_strategies(
+            &mut first,
+            &mut second,
+            (Some(&first_name), Some(&second_name)),
+            config,
+            &mut rng,
+        );
+
+        if let Some(discount) = discount {
+            discounted_results.push(discounted_points(&history, discount, config));
         }
+        results.push(result);
+        rounds.push(history.len());
+        first_history.get_or_insert((history, noise));
     }
 
-    results
+    let n = results.len() as f64;
+    let total = results.iter().fold(GameResult(0.0, 0.0), |total, result| {
+        GameResult(total.0 + result.0, total.1 + result.1)
+    });
+    let (history, noise) = first_history.unwrap_or_default();
+
+    MatchupResult {
+        first_name,
+        second_name,
+        overall_result: GameResult(total.0 / n, total.1 / n),
+        history,
+        noise,
+        results,
+        rounds,
+        discounted_results,
+    }
 }
 
-pub fn play_strategies(first: &mut Box<dyn Strategy>, second: &mut Box<dyn Strategy>) -> (GameResult, GameHistory) {
+/// Points per round where round t counts `discount^t`, normalised by the total weight
+pub fn discounted_points(
+    history: &GameHistory,
+    discount: f64,
+    config: &TournamentConfig,
+) -> GameResult {
+    let mut weight = 1.0;
+    let mut total_weight = 0.0;
+    let mut points = GameResult(0.0, 0.0);
+
+    for GameMove(x, y) in history {
+        let result = play_round(*x, *y, &config.payoff, config.interpolation.as_ref());
+        points = GameResult(points.0 + weight * result.0, points.1 + weight * result.1);
+        total_weight += weight;
+        weight *= discount;
+    }
+
+    if total_weight == 0.0 {
+        return points;
+    }
+    GameResult(points.0 / total_weight, points.1 / total_weight)
+}
+
+/// Plays a single match, `names` are only needed when the tournament reveals opponents
+pub fn play_strategies(
+    first: &mut Box<dyn Strategy>,
+    second: &mut Box<dyn Strategy>,
+    names: (Option<&str>, Option<&str>),
+    config: &TournamentConfig,
+    rng: &mut StdRng,
+) -> (GameResult, GameHistory, Option<NoiseRecord>) {
     let mut results: GameResult = GameResult(0.0, 0.0);
+    let rounds = config.match_length.sample(config.rounds, rng);
+    let announced = config.match_length.is_announced().then_some(rounds);
+
+    let new_context = |rng: &mut StdRng| {
+        Context::new(
+            config.payoff,
+            Arc::clone(&config.interpolation),
+            config.interpretation,
+            announced,
+            StdRng::seed_from_u64(rng.gen()),
+        )
+    };
+    let mut first_ctx = new_context(rng);
+    let mut second_ctx = new_context(rng);
+    let noise = config.noise;
+
+    let match_info = |opponent: Option<&str>| MatchInfo {
+        opponent: opponent
+            .filter(|_| config.reveal_opponents)
+            .map(registry::id_of),
+        rounds: announced,
+        payoff: config.payoff,
+    };
+    first.on_match_start(&match_info(names.1));
+    second.on_match_start(&match_info(names.0));
 
     let mut history = vec![];
-    let mut last_move: Option<GameMove> = None;
+    // what each player has seen, from its own perspective
+    let mut first_view: GameHistory = vec![];
+    let mut second_view: GameHistory = vec![];
+
+    let mut record = (!noise.is_none()).then(|| NoiseRecord {
+        intended: vec![],
+        perceived: vec![],
+    });
 
-    for _ in 0..NUM_ROUNDS {
-        let alt_history: GameHistory = history
-            .iter()
-            .map(|m: &GameMove| m.switch_perspectives())
-            .collect();
+    for _ in 0..rounds {
+        let x = first.next_move(first_view.last().cloned(), &first_view, &mut first_ctx);
+        let y = second.next_move(second_view.last().cloned(), &second_view, &mut second_ctx);
 
-        let x = first.next_move(last_move.clone(), &history);
-        let y = second.next_move(last_move.map(|m| m.switch_perspectives()), &alt_history);
+        let executed_x = noise.action.apply(x, rng);
+        let executed_y = noise.action.apply(y, rng);
 
-        let result = play_round(x, y);
-        let chosen_move = GameMove(x, y);
+        let x_seen_by_second = noise.perception.apply(executed_x, rng);
+        let y_seen_by_first = noise.perception.apply(executed_y, rng);
+
+        let result = play_round(
+            executed_x,
+            executed_y,
+            &config.payoff,
+            config.interpolation.as_ref(),
+        );
 
         results = GameResult(results.0 + result.0, results.1 + result.1);
 
-        history.push(chosen_move.clone());
-        last_move = Some(chosen_move);
+        history.push(GameMove(executed_x, executed_y));
+        first_view.push(GameMove(executed_x, y_seen_by_first));
+        second_view.push(GameMove(executed_y, x_seen_by_second));
+
+        if let Some(record) = &mut record {
+            record.intended.push(GameMove(x, y));
+            record
+                .perceived
+                .push(GameMove(x_seen_by_second, y_seen_by_first));
+        }
     }
 
-    return (results, history);
-}
+    first.on_match_end(&results, &first_view, &mut first_ctx);
+    second.on_match_end(
+        &GameResult(results.1, results.0),
+        &second_view,
+        &mut second_ctx,
+    );
 
-pub fn play_round(x: f64, y: f64) -> GameResult {
-    return GameResult(eval(x, y), eval(y, x));
+    (results, history, record)
 }
 
-fn eval(you: f64, other: f64) -> f64 {
-    let you = you.clamp(COOPERATE, DEFECT);
-    let other = other.clamp(COOPERATE, DEFECT);
-    you - (2.0 * other) + 2.0
+pub fn play_round(
+    x: f64,
+    y: f64,
+    payoff: &Payoff,
+    interpolation: &dyn PayoffInterpolation,
+) -> GameResult {
+    GameResult(
+        interpolation.interpolate(payoff, x, y),
+        interpolation.interpolate(payoff, y, x),
+    )
 }
 
 pub fn from_functional(f: fn(&GameHistory) -> f64) -> Box<dyn Strategy> {
     Box::new(FunctionalStrategyImpl { strategy: f })
-}
\ No newline at end of file
+}
+
+pub fn from_contextual(f: fn(&GameHistory, &mut Context) -> f64) -> Box<dyn Strategy> {
+    Box::new(ContextualStrategyImpl { strategy: f })
+}
@@ -0,0 +1,295 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::{
+    csv::to_file_name,
+    strategies::evolved::{MemoryN, EVOLVED_EXTENSION},
+    tournament::{score_against, TournamentConfig},
+    NamedStrategy, Strategy,
+};
+
+pub const CHECKPOINT_VERSION: u32 = 1;
+pub const CHECKPOINT_EXTENSION: &str = "smg";
+const MAGIC: &[u8; 4] = b"SMCG";
+
+pub type GeneticResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Settings of the genetic algorithm, the matches played to rate individuals follow the `TournamentConfig`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneticConfig {
+    /// Number of past rounds the evolved strategies look at
+    pub depth: usize,
+    pub population_size: usize,
+    pub generations: usize,
+    /// Chance that a single gene is mutated
+    pub mutation_rate: f64,
+    /// Standard deviation of the gaussian added to a mutated gene
+    pub mutation_scale: f64,
+    /// Best individuals copied unchanged into the next generation
+    pub elite: usize,
+    /// Individuals competing for every parent slot
+    pub tournament_size: usize,
+}
+
+/// Best and mean fitness of one generation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub best: f64,
+    pub mean: f64,
+}
+
+/// Everything needed to pick up an interrupted run where it stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub config: GeneticConfig,
+    pub tournament: TournamentConfig,
+    /// Generations evolved so far
+    pub generation: usize,
+    pub population: Vec<MemoryN>,
+    pub history: Vec<GenerationStats>,
+    /// Best individual of the last rated generation, with its fitness
+    pub best: Option<(MemoryN, f64)>,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        GeneticConfig {
+            depth: 1,
+            population_size: 40,
+            generations: 50,
+            mutation_rate: 0.1,
+            mutation_scale: 0.2,
+            elite: 2,
+            tournament_size: 3,
+        }
+    }
+}
+
+impl GeneticConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=4).contains(&self.depth) {
+            return Err(String::from("depth must be between 1 and 4"));
+        }
+        if self.population_size < 2 {
+            return Err(String::from("population size must be at least 2"));
+        }
+        if !(0.0..=1.0).contains(&self.mutation_rate) {
+            return Err(String::from("mutation rate must be between 0 and 1"));
+        }
+        if !(self.mutation_scale >= 0.0 && self.mutation_scale.is_finite()) {
+            return Err(String::from("mutation scale must be a non-negative number"));
+        }
+        if self.elite > self.population_size {
+            return Err(String::from("elite cannot be larger than the population"));
+        }
+        if self.tournament_size == 0 {
+            return Err(String::from("tournament size must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+impl Checkpoint {
+    /// A fresh run with a uniformly random population, drawn from the tournament seed
+    pub fn new(config: GeneticConfig, tournament: TournamentConfig) -> Checkpoint {
+        let mut rng = tournament.stream_rng("genetic population", 0);
+        let table_len = MemoryN::table_len(config.depth);
+
+        let population = (0..config.population_size)
+            .map(|_| MemoryN {
+                depth: config.depth,
+                opening: (0..config.depth).map(|_| rng.gen()).collect(),
+                table: (0..table_len).map(|_| rng.gen()).collect(),
+            })
+            .collect();
+
+        Checkpoint {
+            config,
+            tournament,
+            generation: 0,
+            population,
+            history: vec![],
+            best: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.generation >= self.config.generations
+    }
+
+    /// Rates the current population against `opponents` and replaces it with the next generation
+    pub async fn evolve(&mut self, opponents: &[NamedStrategy]) -> GeneticResult<GenerationStats> {
+        let candidates = self
+            .population
+            .iter()
+            .map(|individual| Box::new(individual.clone()) as Box<dyn Strategy>)
+            .collect();
+        let stream = format!("genetic {}", self.generation);
+        let fitness =
+            score_against(candidates, opponents.to_vec(), &self.tournament, &stream).await?;
+
+        let mut ranked: Vec<_> = (0..fitness.len()).collect();
+        ranked.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+
+        let stats = GenerationStats {
+            best: fitness[ranked[0]],
+            mean: fitness.iter().sum::<f64>() / fitness.len() as f64,
+        };
+        self.best = Some((self.population[ranked[0]].clone(), stats.best));
+
+        let mut rng = self
+            .tournament
+            .stream_rng("genetic breeding", self.generation);
+        let mut next: Vec<_> = ranked
+            .iter()
+            .take(self.config.elite)
+            .map(|&i| self.population[i].clone())
+            .collect();
+
+        while next.len() < self.config.population_size {
+            let mother = self.select(&fitness, &mut rng);
+            let father = self.select(&fitness, &mut rng);
+            let mut child = crossover(mother, father, &mut rng);
+            mutate(&mut child, &self.config, &mut rng)?;
+            next.push(child);
+        }
+
+        self.population = next;
+        self.history.push(stats);
+        self.generation += 1;
+        Ok(stats)
+    }
+
+    /// Tournament selection: the fittest of `tournament_size` uniformly drawn individuals
+    fn select(&self, fitness: &[f64], rng: &mut StdRng) -> &MemoryN {
+        let winner = (0..self.config.tournament_size)
+            .map(|_| rng.gen_range(0..self.population.len()))
+            .max_by(|&a, &b| fitness[a].total_cmp(&fitness[b]))
+            .unwrap_or(0);
+        &self.population[winner]
+    }
+}
+
+/// Uniform crossover, every gene comes from either parent with equal chance
+fn crossover(mother: &MemoryN, father: &MemoryN, rng: &mut StdRng) -> MemoryN {
+    let mut child = mother.clone();
+    for (gene, &other) in child.genes_mut().zip(father.genes()) {
+        if rng.gen() {
+            *gene = other;
+        }
+    }
+    child
+}
+
+/// Adds gaussian noise to a `mutation_rate` share of the genes, keeping every gene a valid move
+fn mutate(child: &mut MemoryN, config: &GeneticConfig, rng: &mut StdRng) -> Result<(), String> {
+    let normal = Normal::new(0.0, config.mutation_scale).map_err(|e| e.to_string())?;
+    let genes: Vec<_> = child.genes_mut().collect();
+
+    for gene in genes {
+        if rng.gen::<f64>() < config.mutation_rate {
+            *gene = (*gene + normal.sample(rng)).clamp(0.0, 1.0);
+        }
+    }
+    Ok(())
+}
+
+/// Files start with `MAGIC` and the little endian `CHECKPOINT_VERSION`, followed by the bincode encoded `Checkpoint`
+pub async fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) -> GeneticResult<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+
+    let mut bytes = Vec::from(*MAGIC);
+    bytes.extend(CHECKPOINT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, checkpoint)?;
+
+    fs::write(path, bytes).await?;
+    Ok(())
+}
+
+pub async fn load_checkpoint(path: &Path) -> GeneticResult<Checkpoint> {
+    let bytes = fs::read(path).await?;
+
+    let (magic, rest) = bytes
+        .split_at_checked(MAGIC.len())
+        .ok_or("File is too short")?;
+    if magic != MAGIC {
+        return Err(format!("{} is not a checkpoint file", path.display()).into());
+    }
+
+    let (version, body) = rest.split_at_checked(4).ok_or("File is too short")?;
+    let version = u32::from_le_bytes(version.try_into()?);
+    if version != CHECKPOINT_VERSION {
+        return Err(format!(
+            "Checkpoint version {} is not supported (expected {})",
+            version, CHECKPOINT_VERSION
+        )
+        .into());
+    }
+
+    Ok(bincode::deserialize(body)?)
+}
+
+/// Writes `strategy` to `dir` as `<name>.memn`, where `evolved::all` picks it up
+pub async fn export_strategy(dir: &Path, name: &str, strategy: &MemoryN) -> GeneticResult<PathBuf> {
+    fs::create_dir_all(dir).await?;
+
+    let path = dir.join(format!("{}.{}", to_file_name(name), EVOLVED_EXTENSION));
+    fs::write(&path, strategy.to_spec(name)).await?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint() -> Checkpoint {
+        // tit for tat, the table is ordered CC, DC, CD, DD
+        let strategy = MemoryN::new(1, vec![0.0], vec![0.0, 0.0, 1.0, 1.0]).unwrap();
+        Checkpoint {
+            config: GeneticConfig::default(),
+            tournament: TournamentConfig::default(),
+            generation: 3,
+            population: vec![strategy.clone(); 4],
+            history: vec![],
+            best: Some((strategy, 2.5)),
+        }
+    }
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("smooth-criminal-{}", std::process::id()))
+            .join(format!("{}.{}", name, CHECKPOINT_EXTENSION))
+    }
+
+    #[tokio::test]
+    async fn checkpoints_load_as_they_were_saved() {
+        let (checkpoint, path) = (checkpoint(), path("round-trip"));
+        save_checkpoint(&path, &checkpoint).await.unwrap();
+
+        let loaded = load_checkpoint(&path).await.unwrap();
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", checkpoint));
+    }
+
+    #[tokio::test]
+    async fn checkpoints_of_another_version_are_rejected() {
+        let path = path("other-version");
+        save_checkpoint(&path, &checkpoint()).await.unwrap();
+
+        let mut bytes = fs::read(&path).await.unwrap();
+        bytes[MAGIC.len()..MAGIC.len() + 4]
+            .copy_from_slice(&(CHECKPOINT_VERSION + 1).to_le_bytes());
+        fs::write(&path, bytes).await.unwrap();
+
+        let e = load_checkpoint(&path).await.unwrap_err().to_string();
+        assert!(e.contains("not supported"), "{}", e);
+    }
+}
@@ -0,0 +1,231 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{payoff::Payoff, COOPERATE, DEFECT};
+
+/// Decides how many points a pair of (possibly continuous) moves is worth
+/// Every model should agree with the payoff matrix when both moves are exactly `COOPERATE` or `DEFECT`
+pub trait PayoffInterpolation: Debug + Send + Sync {
+    /// Text form accepted by `parse_interpolation`
+    fn spec(&self) -> String;
+
+    fn interpolate(&self, payoff: &Payoff, you: f64, other: f64) -> f64;
+}
+
+/// Linear in each move, the model described in the README
+#[derive(Debug, Clone, Copy)]
+pub struct Bilinear;
+
+/// Moves are raised to `exponent` before the bilinear blend
+/// exponent > 1 is convex (partial defections are cheap), exponent < 1 is concave (partial defections count almost fully)
+#[derive(Debug, Clone, Copy)]
+pub struct Power {
+    pub exponent: f64,
+}
+
+/// Moves pass through a logistic curve centered at `midpoint`, rescaled so that 0 and 1 are fixed points
+#[derive(Debug, Clone, Copy)]
+pub struct Sigmoid {
+    pub steepness: f64,
+    pub midpoint: f64,
+}
+
+/// Moves at or above `threshold` are scored as defections, anything else as cooperation
+#[derive(Debug, Clone, Copy)]
+pub struct Threshold {
+    pub threshold: f64,
+}
+
+/// Moves are the probability of defecting, and the score is the expected payoff over the four outcomes
+/// For a 2x2 game this agrees with `Bilinear`, but it is written out so the mixed strategy reading is explicit
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectedValue;
+
+impl PayoffInterpolation for Bilinear {
+    fn spec(&self) -> String {
+        String::from("bilinear")
+    }
+
+    fn interpolate(&self, payoff: &Payoff, you: f64, other: f64) -> f64 {
+        payoff.eval(you, other)
+    }
+}
+
+impl PayoffInterpolation for Power {
+    fn spec(&self) -> String {
+        format!("power({})", self.exponent)
+    }
+
+    fn interpolate(&self, payoff: &Payoff, you: f64, other: f64) -> f64 {
+        let shape = |m: f64| m.clamp(COOPERATE, DEFECT).powf(self.exponent);
+        payoff.eval(shape(you), shape(other))
+    }
+}
+
+impl Sigmoid {
+    fn logistic(&self, m: f64) -> f64 {
+        1.0 / (1.0 + (-self.steepness * (m - self.midpoint)).exp())
+    }
+
+    fn shape(&self, m: f64) -> f64 {
+        let low = self.logistic(COOPERATE);
+        let high = self.logistic(DEFECT);
+        (self.logistic(m.clamp(COOPERATE, DEFECT)) - low) / (high - low)
+    }
+}
+
+impl PayoffInterpolation for Sigmoid {
+    fn spec(&self) -> String {
+        format!("sigmoid({}, {})", self.steepness, self.midpoint)
+    }
+
+    fn interpolate(&self, payoff: &Payoff, you: f64, other: f64) -> f64 {
+        payoff.eval(self.shape(you), self.shape(other))
+    }
+}
+
+impl PayoffInterpolation for Threshold {
+    fn spec(&self) -> String {
+        format!("threshold({})", self.threshold)
+    }
+
+    fn interpolate(&self, payoff: &Payoff, you: f64, other: f64) -> f64 {
+        let snap = |m: f64| {
+            if m >= self.threshold {
+                DEFECT
+            } else {
+                COOPERATE
+            }
+        };
+        payoff.eval(snap(you), snap(other))
+    }
+}
+
+impl PayoffInterpolation for ExpectedValue {
+    fn spec(&self) -> String {
+        String::from("expected")
+    }
+
+    fn interpolate(&self, payoff: &Payoff, you: f64, other: f64) -> f64 {
+        let p = you.clamp(COOPERATE, DEFECT);
+        let q = other.clamp(COOPERATE, DEFECT);
+
+        [
+            ((1.0 - p) * (1.0 - q), payoff.reward),
+            ((1.0 - p) * q, payoff.sucker),
+            (p * (1.0 - q), payoff.temptation),
+            (p * q, payoff.punishment),
+        ]
+        .iter()
+        .map(|(chance, points)| chance * points)
+        .sum()
+    }
+}
+
+/// Parses specs such as `bilinear`, `power(2)`, `sigmoid(10, 0.5)`, `threshold(0.5)` or `expected`
+pub fn parse_interpolation(spec: &str) -> Result<Arc<dyn PayoffInterpolation>, String> {
+    let spec = spec.trim();
+    let (name, args) = match spec.split_once('(') {
+        Some((name, rest)) => {
+            let args = rest
+                .strip_suffix(')')
+                .ok_or_else(|| format!("missing ')' in interpolation '{}'", spec))?;
+            (name.trim(), args)
+        }
+        None => (spec, ""),
+    };
+
+    let args = args
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|a| {
+            a.parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number in interpolation '{}'", a, spec))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let expect_args = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{}' takes {} argument(s), got {}",
+                name,
+                n,
+                args.len()
+            ))
+        }
+    };
+
+    let interpolation: Arc<dyn PayoffInterpolation> = match name.to_lowercase().as_str() {
+        "bilinear" | "linear" => {
+            expect_args(0)?;
+            Arc::new(Bilinear)
+        }
+        "power" => {
+            expect_args(1)?;
+            if args[0] <= 0.0 {
+                return Err(String::from("power exponent must be positive"));
+            }
+            Arc::new(Power { exponent: args[0] })
+        }
+        "sigmoid" => {
+            expect_args(2)?;
+            if args[0] <= 0.0 {
+                return Err(String::from("sigmoid steepness must be positive"));
+            }
+            Arc::new(Sigmoid {
+                steepness: args[0],
+                midpoint: args[1],
+            })
+        }
+        "threshold" => {
+            expect_args(1)?;
+            Arc::new(Threshold { threshold: args[0] })
+        }
+        "expected" | "expected_value" => {
+            expect_args(0)?;
+            Arc::new(ExpectedValue)
+        }
+        _ => return Err(format!("unknown interpolation '{}'", name)),
+    };
+
+    Ok(interpolation)
+}
+
+/// (De)serializes an interpolation as its spec, for use with `#[serde(with = "...")]`
+pub mod spec {
+    use std::sync::Arc;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::{parse_interpolation, PayoffInterpolation};
+
+    pub fn serialize<S: Serializer>(
+        interpolation: &Arc<dyn PayoffInterpolation>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&interpolation.spec())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<dyn PayoffInterpolation>, D::Error> {
+        let spec = String::deserialize(deserializer)?;
+        parse_interpolation(&spec).map_err(D::Error::custom)
+    }
+}
+
+pub fn all() -> Vec<Arc<dyn PayoffInterpolation>> {
+    vec![
+        Arc::new(Bilinear),
+        Arc::new(Power { exponent: 2.0 }),
+        Arc::new(Power { exponent: 0.5 }),
+        Arc::new(Sigmoid {
+            steepness: 10.0,
+            midpoint: 0.5,
+        }),
+        Arc::new(Threshold { threshold: 0.5 }),
+        Arc::new(ExpectedValue),
+    ]
+}
@@ -0,0 +1,133 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::strategies::utils;
+
+/// How strategies built on pure moves read a continuous move as a cooperation or a defection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Interpretation {
+    /// The move is the chance of defecting, every reading flips a new coin
+    #[default]
+    Stochastic,
+    /// Moves at or above the threshold are defections
+    Threshold(f64),
+    /// Moves at or above `high` are defections, at or below `low` cooperations,
+    /// and moves in between read like the same player's move before them (cooperations at the start)
+    Hysteresis { low: f64, high: f64 },
+}
+
+impl Interpretation {
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Interpretation::Threshold(t) if !(t > 0.0 && t <= 1.0) => {
+                Err(String::from("threshold must be above 0 and at most 1"))
+            }
+            Interpretation::Hysteresis { low, high }
+                if !(0.0 <= low && low < high && high <= 1.0) =>
+            {
+                Err(String::from(
+                    "hysteresis bounds must satisfy 0 <= low < high <= 1",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the last of one player's `moves` reads as a defection, earlier moves only matter to `Hysteresis`
+    pub fn is_defection(
+        &self,
+        mut moves: impl DoubleEndedIterator<Item = f64>,
+        rng: &mut impl Rng,
+    ) -> bool {
+        match *self {
+            Interpretation::Stochastic => moves
+                .next_back()
+                .is_some_and(|m| utils::is_defection(&m, rng)),
+            Interpretation::Threshold(t) => moves.next_back().is_some_and(|m| m >= t),
+            Interpretation::Hysteresis { low, high } => moves
+                .rev()
+                .find_map(|m| {
+                    if m >= high {
+                        Some(true)
+                    } else if m <= low {
+                        Some(false)
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(false),
+        }
+    }
+
+    /// Reads every one of a player's `moves` in order, lazily so that stopping early draws no further coins
+    pub fn read<'a, R: Rng>(
+        &self,
+        moves: impl Iterator<Item = f64> + 'a,
+        rng: &'a mut R,
+    ) -> impl Iterator<Item = bool> + 'a {
+        let interpretation = *self;
+
+        moves.scan(false, move |previous, m| {
+            *previous = match interpretation {
+                Interpretation::Stochastic => utils::is_defection(&m, rng),
+                Interpretation::Threshold(t) => m >= t,
+                Interpretation::Hysteresis { low, high } => {
+                    if m >= high {
+                        true
+                    } else if m <= low {
+                        false
+                    } else {
+                        *previous
+                    }
+                }
+            };
+            Some(*previous)
+        })
+    }
+
+    /// Parses `stochastic`, `threshold`, `threshold(t)` or `hysteresis(low, high)`
+    pub fn parse(spec: &str) -> Result<Interpretation, String> {
+        let spec = spec.trim();
+        let interpretation = match spec.to_lowercase().as_str() {
+            "stochastic" => Interpretation::Stochastic,
+            "threshold" => Interpretation::Threshold(0.5),
+            _ => {
+                let (name, args) = spec
+                    .strip_suffix(')')
+                    .and_then(|s| s.split_once('('))
+                    .ok_or_else(|| {
+                        format!(
+                            "expected stochastic, threshold, threshold(t) or hysteresis(low, high), got '{}'",
+                            spec
+                        )
+                    })?;
+                let args = args
+                    .split(',')
+                    .map(|a| {
+                        a.trim().parse::<f64>().map_err(|_| {
+                            format!(
+                                "'{}' is not a number in interpretation '{}'",
+                                a.trim(),
+                                spec
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match (name.trim().to_lowercase().as_str(), &args[..]) {
+                    ("threshold", &[t]) => Interpretation::Threshold(t),
+                    ("hysteresis", &[low, high]) => Interpretation::Hysteresis { low, high },
+                    _ => {
+                        return Err(format!(
+                            "expected threshold(t) or hysteresis(low, high), got '{}'",
+                            spec
+                        ))
+                    }
+                }
+            }
+        };
+
+        interpretation.validate()?;
+        Ok(interpretation)
+    }
+}
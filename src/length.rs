@@ -0,0 +1,77 @@
+use rand::Rng;
+use rand_distr::{Distribution, Geometric};
+use serde::{Deserialize, Serialize};
+
+/// How many rounds a match lasts, and whether the strategies know it in advance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum MatchLength {
+    /// Exactly `TournamentConfig::rounds` rounds, announced through `Context::rounds`
+    #[default]
+    Fixed,
+    /// Exactly `TournamentConfig::rounds` rounds, but strategies are not told when the match ends
+    Hidden,
+    /// After every round the match goes on with this chance, so matches last 1 / (1 - w) rounds on average
+    Geometric(f64),
+}
+
+impl MatchLength {
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            MatchLength::Geometric(w) if !(0.0..1.0).contains(&w) => Err(String::from(
+                "continuation probability must be at least 0 and below 1",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Draws the length of a single match
+    pub fn sample(&self, rounds: usize, rng: &mut impl Rng) -> usize {
+        match *self {
+            MatchLength::Fixed | MatchLength::Hidden => rounds,
+            MatchLength::Geometric(w) => match Geometric::new(1.0 - w) {
+                // the number of failures before the first success, plus the round that is always played
+                Ok(geometric) => 1 + geometric.sample(rng) as usize,
+                Err(_) => rounds,
+            },
+        }
+    }
+
+    /// Whether strategies may know the number of rounds before the match starts
+    pub fn is_announced(&self) -> bool {
+        matches!(self, MatchLength::Fixed)
+    }
+
+    pub fn continuation(&self) -> Option<f64> {
+        match *self {
+            MatchLength::Geometric(w) => Some(w),
+            _ => None,
+        }
+    }
+
+    /// Parses `fixed`, `hidden` or `geometric(w)`
+    pub fn parse(spec: &str) -> Result<MatchLength, String> {
+        let spec = spec.trim();
+        let length = match spec.to_lowercase().as_str() {
+            "fixed" => MatchLength::Fixed,
+            "hidden" => MatchLength::Hidden,
+            _ => {
+                let w = spec
+                    .strip_suffix(')')
+                    .and_then(|s| s.split_once('('))
+                    .filter(|(name, _)| name.trim().eq_ignore_ascii_case("geometric"))
+                    .map(|(_, w)| w.trim())
+                    .ok_or_else(|| {
+                        format!("expected fixed, hidden or geometric(w), got '{}'", spec)
+                    })?;
+
+                let w = w
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' is not a number in match length '{}'", w, spec))?;
+                MatchLength::Geometric(w)
+            }
+        };
+
+        length.validate()?;
+        Ok(length)
+    }
+}
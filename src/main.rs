@@ -1,42 +1,247 @@
 #![allow(dead_code)]
 
+pub mod cli;
 pub mod colors;
+#[cfg(test)]
+mod conformance;
+pub mod csv;
+pub mod ecology;
 pub mod game;
+pub mod genetic;
+pub mod interpolation;
+pub mod interpretation;
+pub mod length;
+pub mod moran;
+pub mod noise;
+pub mod payoff;
+pub mod registry;
+pub mod session;
+pub mod spatial;
+pub mod stats;
+pub mod sweep;
+pub mod tournament;
 pub mod strategies {
+    pub mod axelrod;
     pub mod classic;
+    pub mod combinators;
     pub mod continuous;
+    pub mod evolved;
+    pub mod learning;
+    pub mod memory_one;
+    pub mod scripted;
     pub mod tsvrn9;
     pub mod utils;
+    pub mod zero_determinant;
 }
 pub mod widget {
     pub mod app;
     pub mod grid;
     pub mod inspectors;
     pub mod labels;
+    pub mod plot;
+    pub mod spatial;
+    pub mod sweep;
 }
 
-use std::sync::Arc;
+use std::{error::Error, path::PathBuf, sync::Arc};
 
+use clap::Parser;
+use cli::{Cli, Command};
 use iced::{
     theme::{Custom, Palette},
     window::{Position, Settings},
     Color, Theme,
 };
-use widget::app::ResultsInspector;
+use spatial::SpatialConfig;
+use sweep::SweepConfig;
+use tournament::TournamentConfig;
+use widget::{app::ResultsInspector, spatial::SpatialViewer, sweep::SweepViewer};
 
 use crate::game::*;
 
-pub fn main() -> iced::Result {
-    let palette = Palette {
-        background: Color::BLACK,
-        ..Palette::DARK
-    };
+pub fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    // the viewer shows strategy errors itself
+    let in_viewer = matches!(cli.command, None | Some(Command::View { .. }));
+
+    match cli.command {
+        None => launch_viewer(TournamentConfig::default(), None)?,
+        Some(Command::View {
+            tournament,
+            ecology,
+            file,
+        }) => match file {
+            Some(path) => launch_viewer(TournamentConfig::default(), Some(path))?,
+            None => launch_viewer(tournament.to_config_with_ecology(&ecology)?, None)?,
+        },
+        Some(Command::Run {
+            tournament,
+            output,
+            history,
+        }) => {
+            let config = tournament.to_config()?;
+            tokio::runtime::Runtime::new()?
+                .block_on(cli::run(config, output, history))
+                .map_err(|e| e as Box<dyn Error>)?;
+        }
+        Some(Command::Ecology {
+            tournament,
+            ecology,
+            output,
+        }) => {
+            let config = tournament.to_config_with_ecology(&ecology)?;
+            tokio::runtime::Runtime::new()?
+                .block_on(cli::ecology(config, output))
+                .map_err(|e| e as Box<dyn Error>)?;
+        }
+        Some(Command::Moran {
+            tournament,
+            moran,
+            output,
+        }) => {
+            let config = tournament.to_config()?;
+            let moran = moran.to_config()?;
+            tokio::runtime::Runtime::new()?
+                .block_on(cli::moran(config, moran, output))
+                .map_err(|e| e as Box<dyn Error>)?;
+        }
+        Some(Command::Spatial {
+            tournament,
+            spatial,
+            headless,
+            lattice_generations,
+            output,
+        }) => {
+            let config = tournament.to_config()?.with_resolved_seed();
+            let spatial = spatial.to_config()?;
+            if headless {
+                tokio::runtime::Runtime::new()?
+                    .block_on(cli::spatial(config, spatial, lattice_generations, output))
+                    .map_err(|e| e as Box<dyn Error>)?;
+            } else {
+                launch_spatial_viewer(config, spatial)?;
+            }
+        }
+        Some(Command::Evolve {
+            tournament,
+            genetic,
+            resume,
+            checkpoint_every,
+            name,
+            export,
+            output,
+        }) => {
+            let runtime = tokio::runtime::Runtime::new()?;
+            let checkpoint = match resume {
+                Some(path) => {
+                    let mut checkpoint = runtime
+                        .block_on(genetic::load_checkpoint(&path))
+                        .map_err(|e| e as Box<dyn Error>)?;
+                    if let Some(generations) = genetic.ga_generations {
+                        checkpoint.config.generations = generations;
+                    }
+                    checkpoint
+                }
+                None => genetic::Checkpoint::new(
+                    genetic.to_config()?,
+                    tournament.to_config()?.with_resolved_seed(),
+                ),
+            };
+            runtime
+                .block_on(cli::evolve(
+                    checkpoint,
+                    checkpoint_every,
+                    name,
+                    export,
+                    output,
+                ))
+                .map_err(|e| e as Box<dyn Error>)?;
+        }
+        Some(Command::Sweep {
+            tournament,
+            sweep,
+            headless,
+            output,
+        }) => {
+            let config = tournament.to_config()?.with_resolved_seed();
+            let sweep = sweep.to_config()?;
+            if headless {
+                tokio::runtime::Runtime::new()?
+                    .block_on(cli::sweep(config, sweep, output))
+                    .map_err(|e| e as Box<dyn Error>)?;
+            } else {
+                launch_sweep_viewer(config, sweep)?;
+            }
+        }
+        Some(Command::ListStrategies {
+            family,
+            long,
+            templates,
+        }) => cli::list_strategies(family, long, templates),
+        Some(Command::Info { strategy }) => cli::strategy_info(&strategy)?,
+    }
+
+    if !in_viewer {
+        cli::report_strategy_errors();
+    }
+
+    Ok(())
+}
 
+/// Shows the session at `session` if given, otherwise runs a tournament with `config`
+fn launch_viewer(config: TournamentConfig, session: Option<PathBuf>) -> iced::Result {
     iced::application("Viewer", ResultsInspector::update, ResultsInspector::view)
-        .theme(move |_| Theme::Custom(Arc::new(Custom::new(String::from("Viewer Theme"), palette))))
+        .subscription(ResultsInspector::subscription)
+        .theme(|_| viewer_theme())
+        .window(Settings {
+            position: Position::Centered,
+            ..Default::default()
+        })
+        .run_with(move || match session {
+            Some(path) => ResultsInspector::open(path),
+            None => ResultsInspector::new(config),
+        })
+}
+
+/// Plays a spatial tournament generation by generation in its own window
+fn launch_spatial_viewer(
+    config: TournamentConfig,
+    spatial: SpatialConfig,
+) -> Result<(), Box<dyn Error>> {
+    let strategies = config.resolve_strategies()?;
+
+    iced::application("Spatial Viewer", SpatialViewer::update, SpatialViewer::view)
+        .subscription(SpatialViewer::subscription)
+        .theme(|_| viewer_theme())
         .window(Settings {
             position: Position::Centered,
             ..Default::default()
         })
-        .run_with(ResultsInspector::new)
+        .run_with(move || SpatialViewer::new(strategies, config, spatial))?;
+
+    Ok(())
+}
+
+/// Shows the score of every variant of a memory-one parameter sweep as a heatmap
+fn launch_sweep_viewer(config: TournamentConfig, sweep: SweepConfig) -> Result<(), Box<dyn Error>> {
+    let strategies = config.resolve_strategies()?;
+
+    iced::application("Sweep Viewer", SweepViewer::update, SweepViewer::view)
+        .theme(|_| viewer_theme())
+        .window(Settings {
+            position: Position::Centered,
+            ..Default::default()
+        })
+        .run_with(move || SweepViewer::new(strategies, config, sweep))?;
+
+    Ok(())
+}
+
+fn viewer_theme() -> Theme {
+    let palette = Palette {
+        background: Color::BLACK,
+        ..Palette::DARK
+    };
+
+    Theme::Custom(Arc::new(Custom::new(String::from("Viewer Theme"), palette)))
 }
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, rngs::StdRng, Rng};
+
+use crate::{ecology::payoff_matrix, run_competition, tournament::TournamentConfig, NamedStrategy};
+
+/// Settings of a stochastic finite population simulation, matches themselves follow the `TournamentConfig`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoranConfig {
+    pub population_size: usize,
+    /// Chance that an offspring becomes a uniformly random entered strategy instead of copying its parent
+    pub mutation_rate: f64,
+    /// Independent runs, each seeded from the tournament seed
+    pub runs: usize,
+    /// Births after which a run that has not fixated is given up
+    pub max_steps: usize,
+    /// Starting number of individuals of every entered strategy, `None` spreads the population evenly
+    pub initial_counts: Option<Vec<usize>>,
+}
+
+/// How a single run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoranRun {
+    /// Index of the strategy that took over the whole population, `None` if the run hit `max_steps`
+    pub fixated: Option<usize>,
+    /// Births until fixation (or until the run was given up)
+    pub steps: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MoranResult {
+    pub runs: Vec<MoranRun>,
+}
+
+impl Default for MoranConfig {
+    fn default() -> Self {
+        MoranConfig {
+            population_size: 10,
+            mutation_rate: 0.0,
+            runs: 100,
+            max_steps: 10_000,
+            initial_counts: None,
+        }
+    }
+}
+
+impl MoranConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.population_size < 2 {
+            return Err(String::from("population size must be at least 2"));
+        }
+        if !(0.0..=1.0).contains(&self.mutation_rate) {
+            return Err(String::from("mutation rate must be between 0 and 1"));
+        }
+        if self.runs == 0 {
+            return Err(String::from("runs must be at least 1"));
+        }
+        Ok(())
+    }
+
+    /// Strategy index of every individual at the start of a run
+    pub fn initial_population(&self, num_strategies: usize) -> Result<Vec<usize>, String> {
+        match &self.initial_counts {
+            // an even spread would leave out every strategy past the first `population_size`
+            None if num_strategies > self.population_size => Err(format!(
+                "{} strategies were entered for a population of {}, give initial counts or enter fewer strategies",
+                num_strategies, self.population_size
+            )),
+            None => Ok((0..self.population_size)
+                .map(|i| i % num_strategies)
+                .collect()),
+            Some(counts) if counts.len() != num_strategies => Err(format!(
+                "{} initial counts were given for {} strategies",
+                counts.len(),
+                num_strategies
+            )),
+            Some(counts) if counts.iter().sum::<usize>() != self.population_size => Err(format!(
+                "initial counts add up to {} rather than the population size {}",
+                counts.iter().sum::<usize>(),
+                self.population_size
+            )),
+            Some(counts) => Ok(counts
+                .iter()
+                .enumerate()
+                .flat_map(|(strategy, &count)| std::iter::repeat_n(strategy, count))
+                .collect()),
+        }
+    }
+}
+
+impl MoranResult {
+    /// Share of all runs that ended with `strategy` taking over
+    pub fn fixation_probability(&self, strategy: usize) -> f64 {
+        self.fixations(strategy).count() as f64 / self.runs.len() as f64
+    }
+
+    /// Mean number of births until `strategy` took over, NaN if it never did
+    pub fn mean_fixation_time(&self, strategy: usize) -> f64 {
+        let steps: Vec<_> = self.fixations(strategy).map(|run| run.steps).collect();
+        steps.iter().sum::<usize>() as f64 / steps.len() as f64
+    }
+
+    /// Runs that were given up before any strategy took over
+    pub fn unfixed(&self) -> usize {
+        self.runs.iter().filter(|run| run.fixated.is_none()).count()
+    }
+
+    fn fixations(&self, strategy: usize) -> impl Iterator<Item = &MoranRun> {
+        self.runs
+            .iter()
+            .filter(move |run| run.fixated == Some(strategy))
+    }
+}
+
+/// Runs `moran.runs` independent Moran processes over `strategies`, in parallel
+/// Every pairing is played once up front (with all of its repetitions), so that fitness is looked up rather than played
+/// Learners only carry over between the repetitions of that round robin, not from one birth or death to the next
+pub async fn run_moran(
+    strategies: Vec<NamedStrategy>,
+    config: &TournamentConfig,
+    moran: &MoranConfig,
+) -> Result<MoranResult, String> {
+    moran.validate()?;
+    let n = strategies.len();
+    let initial = moran.initial_population(n)?;
+
+    // individuals of the same strategy meet each other, so self play is needed whatever the tournament says
+    let config = TournamentConfig {
+        self_play: true,
+        ..config.clone()
+    };
+    let results = run_competition(strategies, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+    let payoffs = Arc::new(payoff_matrix(&results, &config, n));
+
+    let config = Arc::new(config);
+    let moran = Arc::new(moran.clone());
+    let mut tasks = vec![];
+
+    for run in 0..moran.runs {
+        let payoffs = Arc::clone(&payoffs);
+        let config = Arc::clone(&config);
+        let moran = Arc::clone(&moran);
+        let population = initial.clone();
+
+        tasks.push(tokio::spawn(async move {
+            let mut rng = config.stream_rng("moran", run);
+            run_process(population, &payoffs, n, &moran, &mut rng)
+        }));
+    }
+
+    let mut runs = vec![];
+    for task in tasks {
+        runs.push(task.await.map_err(|e| e.to_string())?);
+    }
+
+    Ok(MoranResult { runs })
+}
+
+/// One birth-death process: every step an individual reproduces with a chance proportional to its fitness
+/// and its offspring replaces a uniformly random individual
+/// `payoffs` holds the points per round of every one of the `num_strategies` strategies (row) against every other (column)
+pub fn run_process(
+    mut population: Vec<usize>,
+    payoffs: &[f64],
+    num_strategies: usize,
+    moran: &MoranConfig,
+    rng: &mut StdRng,
+) -> MoranRun {
+    for step in 0..moran.max_steps {
+        if let Some(fixated) = fixated(&population) {
+            return MoranRun {
+                fixated: Some(fixated),
+                steps: step,
+            };
+        }
+
+        let fitness = population_fitness(&population, payoffs, num_strategies);
+        let parent = match WeightedIndex::new(&fitness) {
+            Ok(weights) => weights.sample(rng),
+            // every individual scored nothing, so all of them are equally fit
+            Err(_) => rng.gen_range(0..population.len()),
+        };
+
+        let offspring = if rng.gen::<f64>() < moran.mutation_rate {
+            rng.gen_range(0..num_strategies)
+        } else {
+            population[parent]
+        };
+
+        let dead = rng.gen_range(0..population.len());
+        population[dead] = offspring;
+    }
+
+    MoranRun {
+        fixated: fixated(&population),
+        steps: moran.max_steps,
+    }
+}
+
+/// Points per round of every individual against every other individual, shifted so none is negative
+fn population_fitness(population: &[usize], payoffs: &[f64], num_strategies: usize) -> Vec<f64> {
+    let n = population.len();
+    let mut fitness = vec![0.0; n];
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (first, second) = (population[i], population[j]);
+
+            fitness[i] += payoffs[first * num_strategies + second];
+            fitness[j] += payoffs[second * num_strategies + first];
+        }
+    }
+
+    let fitness: Vec<_> = fitness.into_iter().map(|f| f / (n - 1) as f64).collect();
+    let lowest = fitness.iter().copied().fold(f64::INFINITY, f64::min);
+
+    if lowest < 0.0 {
+        fitness.into_iter().map(|f| f - lowest).collect()
+    } else {
+        fitness
+    }
+}
+
+fn fixated(population: &[usize]) -> Option<usize> {
+    let first = *population.first()?;
+    population.iter().all(|&s| s == first).then_some(first)
+}
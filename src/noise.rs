@@ -0,0 +1,104 @@
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+use crate::{GameHistory, COOPERATE, DEFECT};
+
+/// Engine level noise, applied on top of whatever the strategies do themselves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Noise {
+    /// Distorts the move a strategy intended before it is played and scored
+    pub action: NoiseKind,
+    /// Distorts what each player sees of its opponent's executed move, the score is unaffected
+    pub perception: NoiseKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum NoiseKind {
+    #[default]
+    None,
+    /// The move is replaced by its opposite with this chance
+    Flip(f64),
+    /// Normally distributed jitter with this standard deviation, clamped to [COOPERATE, DEFECT]
+    Gaussian(f64),
+}
+
+/// Moves of a noisy match that differ from the executed history, kept so the inspector can show them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseRecord {
+    /// Moves as chosen by the strategies, before action noise
+    pub intended: GameHistory,
+    /// Each player's move as perceived by its opponent
+    pub perceived: GameHistory,
+}
+
+impl Noise {
+    pub fn is_none(&self) -> bool {
+        self.action == NoiseKind::None && self.perception == NoiseKind::None
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.action
+            .validate()
+            .map_err(|e| format!("action noise: {}", e))?;
+        self.perception
+            .validate()
+            .map_err(|e| format!("perception noise: {}", e))
+    }
+}
+
+impl NoiseKind {
+    pub fn apply(&self, m: f64, rng: &mut impl Rng) -> f64 {
+        match *self {
+            NoiseKind::None => m,
+            NoiseKind::Flip(chance) => {
+                if chance > 0.0 && rng.gen::<f64>() < chance {
+                    1.0 - m
+                } else {
+                    m
+                }
+            }
+            NoiseKind::Gaussian(sigma) => match Normal::new(0.0, sigma) {
+                Ok(normal) => (m + normal.sample(rng)).clamp(COOPERATE, DEFECT),
+                Err(_) => m,
+            },
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            NoiseKind::None => Ok(()),
+            NoiseKind::Flip(chance) if (0.0..=1.0).contains(&chance) => Ok(()),
+            NoiseKind::Flip(_) => Err(String::from("flip chance must be between 0 and 1")),
+            NoiseKind::Gaussian(sigma) if sigma.is_finite() && sigma >= 0.0 => Ok(()),
+            NoiseKind::Gaussian(_) => Err(String::from("gaussian sigma must be non-negative")),
+        }
+    }
+
+    /// Parses `none`, `flip(p)` or `gaussian(sigma)`
+    pub fn parse(spec: &str) -> Result<NoiseKind, String> {
+        let spec = spec.trim();
+        if spec.eq_ignore_ascii_case("none") {
+            return Ok(NoiseKind::None);
+        }
+
+        let (name, arg) = spec
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(|| format!("expected none, flip(p) or gaussian(sigma), got '{}'", spec))?;
+
+        let arg = arg
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("'{}' is not a number in noise '{}'", arg, spec))?;
+
+        let kind = match name.trim().to_lowercase().as_str() {
+            "flip" => NoiseKind::Flip(arg),
+            "gaussian" => NoiseKind::Gaussian(arg),
+            name => return Err(format!("unknown noise '{}'", name)),
+        };
+
+        kind.validate()?;
+        Ok(kind)
+    }
+}
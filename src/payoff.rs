@@ -0,0 +1,159 @@
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{COOPERATE, DEFECT};
+
+/// Point values of the four pure outcomes, seen from the player receiving them
+/// R: both cooperate, P: both defect, T: you defect while they cooperate, S: you cooperate while they defect
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Payoff {
+    pub reward: f64,
+    pub punishment: f64,
+    pub temptation: f64,
+    pub sucker: f64,
+}
+
+/// The symmetric 2x2 games that can be described by a `Payoff`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameClass {
+    PrisonersDilemma, // T > R > P > S
+    StagHunt,         // R > T > P > S
+    Chicken,          // T > R > S > P
+    Harmony,          // R > T, S > P
+    Deadlock,         // T > P > R > S
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoffError {
+    NotFinite,
+    NotPrisonersDilemma(GameClass),
+    /// 2R <= T + S, so taking turns exploiting each other beats mutual cooperation
+    AlternationBeatsCooperation,
+}
+
+impl Default for Payoff {
+    fn default() -> Self {
+        Payoff::new(2.0, 1.0, 3.0, 0.0)
+    }
+}
+
+impl Payoff {
+    /// Axelrod's 3/1/5/0 payoff, the one values quoted from the literature assume
+    pub const CANONICAL: Payoff = Payoff::new(3.0, 1.0, 5.0, 0.0);
+
+    pub const fn new(reward: f64, punishment: f64, temptation: f64, sucker: f64) -> Self {
+        Payoff {
+            reward,
+            punishment,
+            temptation,
+            sucker,
+        }
+    }
+
+    pub fn classify(&self) -> GameClass {
+        let Payoff {
+            reward: r,
+            punishment: p,
+            temptation: t,
+            sucker: s,
+        } = *self;
+
+        if t > r && r > p && p > s {
+            GameClass::PrisonersDilemma
+        } else if r > t && t > p && p > s {
+            GameClass::StagHunt
+        } else if t > r && r > s && s > p {
+            GameClass::Chicken
+        } else if t > p && p > r && r > s {
+            GameClass::Deadlock
+        } else if r > t && s > p {
+            GameClass::Harmony
+        } else {
+            GameClass::Other
+        }
+    }
+
+    /// Checks that these values describe an iterated prisoner's dilemma
+    pub fn validate(&self) -> Result<(), PayoffError> {
+        let values = [self.reward, self.punishment, self.temptation, self.sucker];
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(PayoffError::NotFinite);
+        }
+
+        match self.classify() {
+            GameClass::PrisonersDilemma => {}
+            class => return Err(PayoffError::NotPrisonersDilemma(class)),
+        }
+
+        if 2.0 * self.reward <= self.temptation + self.sucker {
+            return Err(PayoffError::AlternationBeatsCooperation);
+        }
+
+        Ok(())
+    }
+
+    /// Points awarded to `you`, with both moves interpreted as a chance of defecting
+    pub fn eval(&self, you: f64, other: f64) -> f64 {
+        let you = you.clamp(COOPERATE, DEFECT);
+        let other = other.clamp(COOPERATE, DEFECT);
+
+        (1.0 - you) * (1.0 - other) * self.reward
+            + (1.0 - you) * other * self.sucker
+            + you * (1.0 - other) * self.temptation
+            + you * other * self.punishment
+    }
+
+    /// Chance of forgiving a defection used by Generous Tit for Tat
+    pub fn generosity(&self) -> f64 {
+        let Payoff {
+            reward: r,
+            punishment: p,
+            temptation: t,
+            sucker: s,
+        } = *self;
+
+        (1.0 - ((t - r) / (r - s))).min((r - p) / (t - p))
+    }
+}
+
+impl fmt::Display for Payoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R={} P={} T={} S={}",
+            self.reward, self.punishment, self.temptation, self.sucker
+        )
+    }
+}
+
+impl fmt::Display for GameClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GameClass::PrisonersDilemma => "Prisoner's Dilemma",
+            GameClass::StagHunt => "Stag Hunt",
+            GameClass::Chicken => "Chicken",
+            GameClass::Harmony => "Harmony",
+            GameClass::Deadlock => "Deadlock",
+            GameClass::Other => "Unclassified game",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for PayoffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayoffError::NotFinite => f.write_str("payoff values must be finite"),
+            PayoffError::NotPrisonersDilemma(class) => {
+                write!(f, "payoff is a {} rather than a Prisoner's Dilemma", class)
+            }
+            PayoffError::AlternationBeatsCooperation => {
+                f.write_str("2R must be greater than T + S for an iterated Prisoner's Dilemma")
+            }
+        }
+    }
+}
+
+impl Error for PayoffError {}
@@ -0,0 +1,836 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::{
+    strategies::{
+        axelrod, classic, combinators, continuous, evolved, learning, memory_one, scripted, tsvrn9,
+        zero_determinant,
+    },
+    NamedStrategy, Strategy,
+};
+
+/// Broad kind of a strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    /// Plays pure moves, reading continuous moves following `Context::interpretation`
+    Classic,
+    /// Plays and reads moves anywhere between cooperating and defecting
+    Continuous,
+    /// Contributed, evolved or assembled strategies
+    Custom,
+}
+
+/// How many past rounds a strategy looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Memory {
+    Rounds(usize),
+    /// The whole match, or state carried from round to round
+    Full,
+}
+
+/// Everything known about a strategy besides how it plays
+#[derive(Debug, Clone)]
+pub struct StrategyInfo {
+    /// Stable identifier, lowercase words joined by dashes, unlike `name` it is never reworded
+    pub id: String,
+    pub name: String,
+    pub family: Family,
+    pub description: String,
+    /// Empty when unknown
+    pub author: String,
+    pub parameters: Vec<(String, f64)>,
+    /// Whether it draws random numbers beyond reading continuous moves
+    pub stochastic: bool,
+    pub memory: Memory,
+}
+
+/// A strategy and its `StrategyInfo`, as listed by each module's `all()`
+#[derive(Clone)]
+pub struct Registered {
+    pub info: StrategyInfo,
+    pub strategy: Box<dyn Strategy>,
+}
+
+/// A value a `Template` is built with
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub default: f64,
+    pub range: RangeInclusive<f64>,
+    /// Whether only whole numbers are accepted
+    pub integer: bool,
+}
+
+/// Builds a strategy from a spec such as `npavlov(n=16)`, for variants that are not registered
+/// Combinators are also built out of other strategies, such as `noisy(tit-for-tat, sigma=0.1)`
+#[derive(Clone)]
+pub struct Template {
+    pub id: &'static str,
+    /// How the strategies it is built out of are written in a spec, empty when it takes none
+    pub strategies: &'static str,
+    pub parameters: Vec<Parameter>,
+    build: Arc<Build>,
+}
+
+/// Finds the strategies given to a combinator, `resolve` unless the registry is being built
+type Resolve<'a> = dyn Fn(&str) -> Result<Registered, String> + 'a;
+
+/// Receives the id and name of the variant, the strategies it is built out of, then one value per parameter in order, all within their range
+type Build = dyn Fn(&str, &str, Vec<Argument>, &[f64]) -> Result<Registered, String> + Send + Sync;
+
+/// A strategy given to a combinator, `weight` is set when it is written as `weight * strategy`
+#[derive(Clone)]
+pub struct Argument {
+    pub weight: Option<f64>,
+    pub strategy: Registered,
+}
+
+impl Family {
+    /// Parses `classic`, `continuous` or `custom`
+    pub fn parse(spec: &str) -> Result<Family, String> {
+        match spec.trim().to_lowercase().as_str() {
+            "classic" => Ok(Family::Classic),
+            "continuous" => Ok(Family::Continuous),
+            "custom" => Ok(Family::Custom),
+            _ => Err(format!(
+                "expected classic, continuous or custom, got '{}'",
+                spec
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Family::Classic => "classic",
+            Family::Continuous => "continuous",
+            Family::Custom => "custom",
+        })
+    }
+}
+
+impl fmt::Display for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Memory::Rounds(1) => f.write_str("1 round"),
+            Memory::Rounds(n) => write!(f, "{} rounds", n),
+            Memory::Full => f.write_str("whole match"),
+        }
+    }
+}
+
+impl StrategyInfo {
+    /// `name = value` pairs joined by commas
+    pub fn parameter_list(&self) -> String {
+        self.parameters
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Registered {
+    /// Deterministic, with no description or parameters and a memory of the whole match until told otherwise
+    pub fn new(id: &str, name: &str, family: Family, strategy: Box<dyn Strategy>) -> Self {
+        Registered {
+            info: StrategyInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                family,
+                description: String::new(),
+                author: String::new(),
+                parameters: vec![],
+                stochastic: false,
+                memory: Memory::Full,
+            },
+            strategy,
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.info.description = description.to_string();
+        self
+    }
+
+    pub fn author(mut self, author: &str) -> Self {
+        self.info.author = author.to_string();
+        self
+    }
+
+    pub fn parameter(mut self, name: &str, value: f64) -> Self {
+        self.info.parameters.push((name.to_string(), value));
+        self
+    }
+
+    pub fn stochastic(mut self) -> Self {
+        self.info.stochastic = true;
+        self
+    }
+
+    pub fn memory(mut self, rounds: usize) -> Self {
+        self.info.memory = Memory::Rounds(rounds);
+        self
+    }
+}
+
+impl Memory {
+    /// The longer of both memories
+    pub fn longest(self, other: Memory) -> Memory {
+        match (self, other) {
+            (Memory::Rounds(a), Memory::Rounds(b)) => Memory::Rounds(a.max(b)),
+            _ => Memory::Full,
+        }
+    }
+}
+
+impl Argument {
+    /// The strategy's id, or its spec when it was built from one, with its weight when it has one
+    fn spec(&self) -> String {
+        let info = &self.strategy.info;
+        let strategy = if is_spec(&info.name) {
+            &info.name
+        } else {
+            &info.id
+        };
+        match self.weight {
+            Some(weight) => format!("{} * {}", weight, strategy),
+            None => strategy.clone(),
+        }
+    }
+}
+
+impl Template {
+    pub fn new(
+        id: &'static str,
+        build: impl Fn(&str, &str, &[f64]) -> Registered + Send + Sync + 'static,
+    ) -> Self {
+        Template {
+            id,
+            strategies: "",
+            parameters: vec![],
+            build: Arc::new(move |variant_id, name, arguments, values| {
+                if !arguments.is_empty() {
+                    return Err(format!("{} does not take strategies", id));
+                }
+                Ok(build(variant_id, name, values))
+            }),
+        }
+    }
+
+    /// A template built out of other strategies, `build` checks how many it was given
+    pub fn combinator(
+        id: &'static str,
+        strategies: &'static str,
+        build: impl Fn(&str, &str, Vec<Argument>, &[f64]) -> Result<Registered, String>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Template {
+            id,
+            strategies,
+            parameters: vec![],
+            build: Arc::new(build),
+        }
+    }
+
+    pub fn parameter(
+        mut self,
+        name: &'static str,
+        default: f64,
+        range: RangeInclusive<f64>,
+    ) -> Self {
+        self.parameters.push(Parameter {
+            name,
+            default,
+            range,
+            integer: false,
+        });
+        self
+    }
+
+    pub fn integer_parameter(
+        mut self,
+        name: &'static str,
+        default: usize,
+        range: RangeInclusive<usize>,
+    ) -> Self {
+        self.parameters.push(Parameter {
+            name,
+            default: default as f64,
+            range: *range.start() as f64..=*range.end() as f64,
+            integer: true,
+        });
+        self
+    }
+
+    /// Strategies and values of every parameter from the arguments of a spec, separated by commas
+    /// Parameters are written `name=value` and keep their default when left out, anything else is a strategy found by `resolve`
+    pub fn arguments(
+        &self,
+        arguments: &str,
+        resolve: &Resolve,
+    ) -> Result<(Vec<Argument>, Vec<f64>), String> {
+        let mut strategies = vec![];
+        let mut values: Vec<Option<f64>> = vec![None; self.parameters.len()];
+
+        for argument in split_outside_parentheses(arguments, ',') {
+            let argument = argument.trim();
+            if argument.is_empty() {
+                continue;
+            }
+
+            let (name, value) = match split_outside_parentheses(argument, '=')[..] {
+                [name, value] => (name.trim(), value.trim()),
+                [_] if !self.strategies.is_empty() => {
+                    strategies.push(self.argument(argument, resolve)?);
+                    continue;
+                }
+                _ => {
+                    return Err(format!(
+                        "expected name=value in '{}', got '{}'",
+                        self.id, argument
+                    ))
+                }
+            };
+
+            let index = self
+                .parameters
+                .iter()
+                .position(|p| p.name == name)
+                .ok_or_else(|| {
+                    if self.parameters.is_empty() {
+                        return format!("{} takes no parameters, got '{}'", self.id, name);
+                    }
+                    format!(
+                        "{} has no parameter '{}', expected {}",
+                        self.id,
+                        name,
+                        self.parameter_names()
+                    )
+                })?;
+            let parameter = &self.parameters[index];
+            let value = value.parse::<f64>().map_err(|_| {
+                format!(
+                    "parameter '{}' of {}: '{}' is not a number",
+                    name, self.id, value
+                )
+            })?;
+
+            if values[index].replace(value).is_some() {
+                return Err(format!(
+                    "parameter '{}' of {} is given twice",
+                    name, self.id
+                ));
+            }
+            if !parameter.range.contains(&value) {
+                return Err(format!(
+                    "parameter '{}' of {} must be between {} and {}, got {}",
+                    name,
+                    self.id,
+                    parameter.range.start(),
+                    parameter.range.end(),
+                    value
+                ));
+            }
+            if parameter.integer && value.fract() != 0.0 {
+                return Err(format!(
+                    "parameter '{}' of {} must be a whole number, got {}",
+                    name, self.id, value
+                ));
+            }
+        }
+
+        let values = values
+            .into_iter()
+            .zip(&self.parameters)
+            .map(|(value, parameter)| value.unwrap_or(parameter.default))
+            .collect();
+        Ok((strategies, values))
+    }
+
+    /// A strategy argument, `strategy` or `weight * strategy`
+    fn argument(&self, argument: &str, resolve: &Resolve) -> Result<Argument, String> {
+        let (weight, strategy) = match split_outside_parentheses(argument, '*')[..] {
+            [weight, strategy] => {
+                let weight = weight.trim().parse::<f64>().map_err(|_| {
+                    format!(
+                        "weight of '{}' in {}: '{}' is not a number",
+                        strategy.trim(),
+                        self.id,
+                        weight.trim()
+                    )
+                })?;
+                (Some(weight), strategy.trim())
+            }
+            _ => (None, argument),
+        };
+
+        Ok(Argument {
+            weight,
+            strategy: resolve(strategy)?,
+        })
+    }
+
+    /// The variant built out of these strategies and values, named by its canonical spec: every argument spelled out,
+    /// strategies in the order given (they are told apart by position) and parameters sorted by name
+    /// Its id is that spec made into an id, so the same variant gets the same id however it was written
+    pub fn build(&self, arguments: Vec<Argument>, values: &[f64]) -> Result<Registered, String> {
+        let mut parameters: Vec<_> = self.parameters.iter().zip(values).collect();
+        parameters.sort_by_key(|(parameter, _)| parameter.name);
+
+        let spec = format!(
+            "{}({})",
+            self.id,
+            arguments
+                .iter()
+                .map(Argument::spec)
+                .chain(
+                    parameters
+                        .into_iter()
+                        .map(|(parameter, value)| format!("{}={}", parameter.name, value))
+                )
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        (self.build)(&to_id(&spec), &spec, arguments, values)
+    }
+
+    /// Names of every parameter joined by commas
+    pub fn parameter_names(&self) -> String {
+        self.parameters
+            .iter()
+            .map(|p| p.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Every known strategy, built-in ones first
+/// Built once and kept until `reload`, as it reads and compiles every file in the strategies directory
+pub fn registry() -> Vec<Registered> {
+    with_registry(<[Registered]>::to_vec)
+}
+
+/// Runs `f` on the kept registry without copying it, building it first when needed
+/// `f` must not use the registry itself
+pub fn with_registry<R>(f: impl FnOnce(&[Registered]) -> R) -> R {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    f(registry.get_or_insert_with(load))
+}
+
+/// Makes the next use of the registry read the strategies directory again, forgetting every error kept so far
+pub fn reload() {
+    *REGISTRY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    ids().lock().unwrap_or_else(|e| e.into_inner()).clear();
+    clear_errors();
+}
+
+/// `None` until first used and after every `reload`
+static REGISTRY: Mutex<Option<Vec<Registered>>> = Mutex::new(None);
+
+fn load() -> Vec<Registered> {
+    let mut registry: Vec<_> = vec![
+        classic::all(),
+        axelrod::all(),
+        continuous::all(),
+        tsvrn9::all(),
+        learning::all(),
+        zero_determinant::all(),
+        memory_one::all(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // presets are built out of the strategies above, which cannot be resolved through the registry while it is built
+    let presets = combinators::presets()
+        .into_iter()
+        .map(|spec| build_with(spec, &|name| lookup(&registry, name)))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("presets are built out of registered strategies");
+    registry.extend(presets);
+
+    add_files(
+        &mut registry,
+        evolved::all().into_iter().chain(scripted::all()),
+    );
+    registry
+}
+
+/// Adds strategies loaded from files, leaving out those whose id or name is already taken
+/// Results are keyed by name, so a file must not shadow a built-in strategy or block the tournaments that enter every strategy
+fn add_files(
+    registry: &mut Vec<Registered>,
+    files: impl IntoIterator<Item = (PathBuf, Registered)>,
+) {
+    for (path, registered) in files {
+        let taken = registry
+            .iter()
+            .find(|r| r.info.id == registered.info.id || r.info.name == registered.info.name);
+
+        match taken {
+            Some(existing) => ERRORS.record(
+                &path.display().to_string(),
+                &format!(
+                    "'{}' is already taken by {}, the file is left out",
+                    registered.info.name, existing.info.id
+                ),
+            ),
+            None => registry.push(registered),
+        }
+    }
+}
+
+/// Files left out of the registry, because they used the id or name of another strategy
+static ERRORS: ErrorLog = ErrorLog::new();
+
+/// Every strategy that can be built from a spec
+pub fn templates() -> Vec<Template> {
+    vec![
+        classic::templates(),
+        axelrod::templates(),
+        continuous::templates(),
+        tsvrn9::templates(),
+        learning::templates(),
+        zero_determinant::templates(),
+        memory_one::templates(),
+        combinators::templates(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// The template, strategies and parameter values of a spec such as `detente(comfort=0.5, trust=0.2)`, or just `detente` for the defaults
+pub fn parse(spec: &str) -> Result<(Template, Vec<Argument>, Vec<f64>), String> {
+    parse_with(spec, &resolve)
+}
+
+/// Like `parse`, finding the strategies given to combinators with `resolve`
+fn parse_with(
+    spec: &str,
+    resolve: &Resolve,
+) -> Result<(Template, Vec<Argument>, Vec<f64>), String> {
+    let spec = spec.trim();
+    let (id, arguments) = match spec.strip_suffix(')') {
+        Some(call) => call
+            .split_once('(')
+            .ok_or_else(|| format!("expected name(parameter=value, ...), got '{}'", spec))?,
+        None => (spec, ""),
+    };
+    let id = id.trim();
+
+    let template = templates()
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| {
+            format!(
+                "'{}' does not take parameters, expected one of {}",
+                id,
+                templates()
+                    .iter()
+                    .map(|t| t.id)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+    let (strategies, values) = template.arguments(arguments, resolve)?;
+    Ok((template, strategies, values))
+}
+
+/// The variant described by `spec`
+pub fn build(spec: &str) -> Result<Registered, String> {
+    build_with(spec, &resolve)
+}
+
+fn build_with(spec: &str, resolve: &Resolve) -> Result<Registered, String> {
+    let (template, strategies, values) = parse_with(spec, resolve)?;
+    template.build(strategies, &values)
+}
+
+/// Whether `name` looks like a spec with parameters rather than an id or a name
+pub fn is_spec(name: &str) -> bool {
+    name.trim_end().ends_with(')')
+}
+
+/// The strategy with this id, or failing that this name, or failing that this spec
+pub fn find(id_or_name: &str) -> Option<Registered> {
+    resolve(id_or_name).ok()
+}
+
+/// Like `find`, but says why a spec could not be built
+pub fn resolve(id_or_name: &str) -> Result<Registered, String> {
+    with_registry(|registry| lookup(registry, id_or_name))
+}
+
+/// The strategy of `registry` with this id, or failing that this name, or failing that this spec
+/// Strategies given to a spec are looked up in `registry` as well
+fn lookup(registry: &[Registered], id_or_name: &str) -> Result<Registered, String> {
+    let registered = registry
+        .iter()
+        .find(|r| r.info.id == id_or_name)
+        .or_else(|| registry.iter().find(|r| r.info.name == id_or_name));
+
+    match registered {
+        Some(registered) => Ok(registered.clone()),
+        None if is_spec(id_or_name) || templates().iter().any(|t| t.id == id_or_name) => {
+            build_with(id_or_name, &|name| lookup(registry, name))
+        }
+        None => Err(format!("unknown strategy '{}'", id_or_name)),
+    }
+}
+
+/// Pieces of `s` between every `separator` that is not inside parentheses
+fn split_outside_parentheses(s: &str, separator: char) -> Vec<&str> {
+    let mut pieces = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                pieces.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    pieces.push(&s[start..]);
+    pieces
+}
+
+/// Info of every named strategy, `None` for names that are neither registered nor specs (such as strategies of an older session)
+pub fn infos(names: &[String]) -> Vec<Option<StrategyInfo>> {
+    let registered: Vec<_> = with_registry(|registry| {
+        names
+            .iter()
+            .map(|name| {
+                registry
+                    .iter()
+                    .find(|r| &r.info.name == name)
+                    .map(|r| r.info.clone())
+            })
+            .collect()
+    });
+
+    registered
+        .into_iter()
+        .zip(names)
+        .map(|(info, name)| info.or_else(|| build(name).ok().map(|r| r.info)))
+        .collect()
+}
+
+/// First error of every strategy loaded from a file that failed to load or to play, by file or strategy
+/// Kept rather than printed, and reported once by the command or the viewer
+#[derive(Debug, Default)]
+pub struct ErrorLog(Mutex<BTreeMap<String, String>>);
+
+impl ErrorLog {
+    pub const fn new() -> Self {
+        ErrorLog(Mutex::new(BTreeMap::new()))
+    }
+
+    /// Keeps `error` unless `source` already failed
+    pub fn record(&self, source: &str, error: &str) {
+        let mut errors = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        errors
+            .entry(source.to_string())
+            .or_insert_with(|| error.to_string());
+    }
+
+    /// Every source with its first error, sorted by source
+    pub fn errors(&self) -> Vec<(String, String)> {
+        let errors = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        errors
+            .iter()
+            .map(|(source, error)| (source.clone(), error.clone()))
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// Every error kept by the strategies loaded from files, evolved tables first, then scripts, then files left out as duplicates
+pub fn errors() -> Vec<(String, String)> {
+    let mut errors = evolved::errors();
+    errors.extend(scripted::errors());
+    errors.extend(ERRORS.errors());
+    errors
+}
+
+pub fn clear_errors() {
+    evolved::clear_errors();
+    scripted::clear_errors();
+    ERRORS.clear();
+}
+
+/// Id of the strategy with this name, or the name made into an id for strategies that are neither registered nor specs
+pub fn id_of(name: &str) -> String {
+    let known = with_registry(|registry| {
+        registry
+            .iter()
+            .find(|r| r.info.name == name)
+            .map(|r| r.info.id.clone())
+    });
+    if let Some(id) = known {
+        return id;
+    }
+
+    let mut ids = ids().lock().unwrap_or_else(|e| e.into_inner());
+    ids.entry(name.to_string())
+        .or_insert_with(|| {
+            build(name)
+                .map(|r| r.info.id)
+                .unwrap_or_else(|_| to_id(name))
+        })
+        .clone()
+}
+
+/// Ids of the specs and unknown names `id_of` was asked about, forgotten on `reload`
+fn ids() -> &'static Mutex<HashMap<String, String>> {
+    static IDS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    IDS.get_or_init(Default::default)
+}
+
+impl From<Registered> for NamedStrategy {
+    fn from(registered: Registered) -> Self {
+        (registered.info.name, registered.strategy)
+    }
+}
+
+/// Lowercase words of `name` joined by dashes
+pub fn to_id(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_functional, tournament::TournamentConfig};
+
+    fn file(id: &str, name: &str) -> Registered {
+        Registered::new(
+            id,
+            name,
+            Family::Custom,
+            from_functional(classic::unconditional_defector),
+        )
+    }
+
+    #[test]
+    fn files_that_reuse_an_id_or_a_name_are_left_out() {
+        let mut registry = classic::all();
+        let count = registry.len();
+        let shadow = PathBuf::from("strategies/shadow.rhai");
+        let unique = PathBuf::from("strategies/unique.rhai");
+
+        add_files(
+            &mut registry,
+            [
+                (shadow.clone(), file("script-shadow", "Classic Tit for Tat")),
+                (unique, file("script-unique", "Unique")),
+                (PathBuf::from("b.memn"), file("script-unique", "Unique Too")),
+            ],
+        );
+
+        assert_eq!(registry.len(), count + 1);
+        let tit_for_tat = registry
+            .iter()
+            .find(|r| r.info.name == "Classic Tit for Tat")
+            .unwrap();
+        assert_eq!(tit_for_tat.info.id, "classic-tit-for-tat");
+
+        let errors = ERRORS.errors();
+        assert!(errors
+            .iter()
+            .any(|(source, _)| *source == shadow.display().to_string()));
+        assert!(errors.iter().any(|(source, _)| source == "b.memn"));
+    }
+
+    fn error(spec: &str) -> String {
+        match build(spec) {
+            Ok(registered) => panic!("{} was accepted as {}", spec, registered.info.name),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn out_of_range_parameters_are_named() {
+        assert!(error("detente(comfort=1.5)").contains("'comfort'"));
+        assert!(error("detente(trust=-0.1)").contains("'trust'"));
+        assert!(error("npavlov(n=0)").contains("'n'"));
+        assert!(error("npavlov(n=2.5)").contains("whole number"));
+        assert!(error("npavlov(n=many)").contains("not a number"));
+    }
+
+    #[test]
+    fn unknown_parameters_are_named() {
+        let e = error("detente(warmth=0.5)");
+        assert!(
+            e.contains("'warmth'") && e.contains("comfort, trust"),
+            "{}",
+            e
+        );
+        assert!(error("detente(comfort=0.5, comfort=0.6)").contains("twice"));
+        assert!(error("npavlov(16)").contains("name=value"));
+    }
+
+    #[test]
+    fn nested_specs_are_built_with_their_own_parameters() {
+        let (_, arguments, values) =
+            parse("noisy(detente(trust=0.2, comfort=0.5), sigma=0.05)").unwrap();
+        assert_eq!(values, [0.05]);
+        assert_eq!(
+            arguments[0].strategy.info.parameters,
+            [("comfort".to_string(), 0.5), ("trust".to_string(), 0.2)]
+        );
+
+        let built = build("noisy(detente(trust=0.2, comfort=0.5), sigma=0.05)").unwrap();
+        assert_eq!(
+            built.info.name,
+            "noisy(detente(comfort=0.5, trust=0.2), sigma=0.05)"
+        );
+        assert!(error("noisy(detente(comfort=2), sigma=0.05)").contains("'comfort'"));
+    }
+
+    #[test]
+    fn strategies_entered_twice_are_rejected() {
+        let entered = |names: &[&str]| TournamentConfig {
+            included_strategies: Some(names.iter().map(|name| name.to_string()).collect()),
+            ..Default::default()
+        };
+
+        // by its id and its name
+        let e = entered(&["classic-tit-for-tat", "Classic Tit for Tat"])
+            .resolve_strategies()
+            .err()
+            .unwrap();
+        assert!(e.contains("more than once"), "{}", e);
+        // by two spellings of the same spec
+        assert!(entered(&[
+            "detente(trust=0.2, comfort=0.5)",
+            "detente(comfort=0.5, trust=0.2)"
+        ])
+        .resolve_strategies()
+        .is_err());
+        assert!(entered(&["detente(comfort=0.5)", "detente(comfort=0.6)"])
+            .resolve_strategies()
+            .is_ok());
+    }
+}
@@ -0,0 +1,145 @@
+use std::{error::Error, path::Path, sync::Arc};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::{stats::Stat, tournament::TournamentConfig, MatchupResult};
+
+/// Bumped whenever the layout of `Session` changes
+pub const SESSION_VERSION: u32 = 1;
+pub const SESSION_EXTENSION: &str = "smc";
+const MAGIC: &[u8; 4] = b"SMCR";
+
+pub type SessionResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// A finished tournament, everything the viewer needs without running the simulation again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub config: TournamentConfig,
+    pub strategy_names: Vec<String>,
+    pub matchup_results: Vec<MatchupResult>,
+    pub stats: Vec<(String, Stat)>,
+}
+
+impl Session {
+    pub fn new(
+        config: TournamentConfig,
+        strategy_names: Vec<String>,
+        matchup_results: Vec<MatchupResult>,
+        stats: &IndexMap<String, Arc<Stat>>,
+    ) -> Self {
+        Session {
+            config,
+            strategy_names,
+            matchup_results,
+            stats: stats
+                .iter()
+                .map(|(name, stat)| (name.clone(), Stat::clone(stat)))
+                .collect(),
+        }
+    }
+
+    pub fn stats(&self) -> IndexMap<String, Arc<Stat>> {
+        self.stats
+            .iter()
+            .map(|(name, stat)| (name.clone(), Arc::new(stat.clone())))
+            .collect()
+    }
+}
+
+/// Files start with `MAGIC` and the little endian `SESSION_VERSION`, followed by the bincode encoded `Session`
+pub async fn save_session(path: &Path, session: &Session) -> SessionResult<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+
+    let mut bytes = Vec::from(*MAGIC);
+    bytes.extend(SESSION_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, session)?;
+
+    fs::write(path, bytes).await?;
+    Ok(())
+}
+
+pub async fn load_session(path: &Path) -> SessionResult<Session> {
+    let bytes = fs::read(path).await?;
+
+    let (magic, rest) = bytes
+        .split_at_checked(MAGIC.len())
+        .ok_or("File is too short")?;
+    if magic != MAGIC {
+        return Err(format!("{} is not a session file", path.display()).into());
+    }
+
+    let (version, body) = rest.split_at_checked(4).ok_or("File is too short")?;
+    let version = u32::from_le_bytes(version.try_into()?);
+    if version != SESSION_VERSION {
+        return Err(format!(
+            "Session version {} is not supported (expected {})",
+            version, SESSION_VERSION
+        )
+        .into());
+    }
+
+    Ok(bincode::deserialize(body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{play_matchup, registry};
+
+    fn session() -> Session {
+        let config = TournamentConfig {
+            rounds: 20,
+            seed: Some(0),
+            ..Default::default()
+        };
+        let strategy = |id: &str| registry::resolve(id).unwrap().strategy;
+        let result = play_matchup(
+            String::from("Grim"),
+            strategy("grim"),
+            String::from("Pavlov"),
+            strategy("pavlov"),
+            &config,
+        );
+
+        Session {
+            config,
+            strategy_names: vec![String::from("Grim"), String::from("Pavlov")],
+            matchup_results: vec![result],
+            stats: vec![],
+        }
+    }
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("smooth-criminal-{}", std::process::id()))
+            .join(format!("{}.{}", name, SESSION_EXTENSION))
+    }
+
+    #[tokio::test]
+    async fn sessions_load_as_they_were_saved() {
+        let (session, path) = (session(), path("round-trip"));
+        save_session(&path, &session).await.unwrap();
+
+        let loaded = load_session(&path).await.unwrap();
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", session));
+    }
+
+    #[tokio::test]
+    async fn sessions_of_another_version_are_rejected() {
+        let path = path("other-version");
+        save_session(&path, &session()).await.unwrap();
+
+        let mut bytes = fs::read(&path).await.unwrap();
+        bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(SESSION_VERSION + 1).to_le_bytes());
+        fs::write(&path, bytes).await.unwrap();
+
+        let e = load_session(&path).await.unwrap_err().to_string();
+        assert!(e.contains("not supported"), "{}", e);
+    }
+}
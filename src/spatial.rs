@@ -0,0 +1,211 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{play_strategies, tournament::TournamentConfig, GameResult, NamedStrategy};
+
+/// Which sites around a site count as its neighbours
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Neighbourhood {
+    /// The four orthogonal sites
+    #[default]
+    VonNeumann,
+    /// The eight orthogonal and diagonal sites
+    Moore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpatialConfig {
+    pub width: usize,
+    pub height: usize,
+    pub neighbourhood: Neighbourhood,
+    /// Whether the edges wrap around, making the lattice a torus
+    pub wrap: bool,
+}
+
+/// A rectangular lattice holding one strategy (an index into the entered strategies) per site, row major
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lattice {
+    pub width: usize,
+    pub height: usize,
+    pub sites: Vec<usize>,
+    pub generation: usize,
+}
+
+impl Default for SpatialConfig {
+    fn default() -> Self {
+        SpatialConfig {
+            width: 30,
+            height: 30,
+            neighbourhood: Neighbourhood::default(),
+            wrap: true,
+        }
+    }
+}
+
+impl Neighbourhood {
+    /// Parses `von-neumann` or `moore`
+    pub fn parse(spec: &str) -> Result<Neighbourhood, String> {
+        match spec.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "von-neumann" | "vonneumann" => Ok(Neighbourhood::VonNeumann),
+            "moore" => Ok(Neighbourhood::Moore),
+            _ => Err(format!("expected von-neumann or moore, got '{}'", spec)),
+        }
+    }
+
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::VonNeumann => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Neighbourhood::Moore => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+impl SpatialConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(String::from("the lattice must be at least 1 by 1"));
+        }
+        Ok(())
+    }
+
+    /// Indices of the distinct neighbours of `site`, without `site` itself
+    pub fn neighbours(&self, site: usize) -> Vec<usize> {
+        let (row, col) = ((site / self.width) as isize, (site % self.width) as isize);
+        let (width, height) = (self.width as isize, self.height as isize);
+
+        let mut neighbours: Vec<_> = self
+            .neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(|(dr, dc)| {
+                let (r, c) = (row + dr, col + dc);
+                if self.wrap {
+                    Some((r.rem_euclid(height), c.rem_euclid(width)))
+                } else if (0..height).contains(&r) && (0..width).contains(&c) {
+                    Some((r, c))
+                } else {
+                    None
+                }
+            })
+            .map(|(r, c)| (r * width + c) as usize)
+            .filter(|&n| n != site)
+            .collect();
+
+        // small wrapped lattices reach the same site from several sides
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+}
+
+impl Lattice {
+    /// Every site gets a uniformly random strategy, drawn from the tournament seed
+    /// `num_strategies` must be at least 1, which `TournamentConfig::resolve_strategies` guarantees
+    pub fn random(
+        spatial: &SpatialConfig,
+        num_strategies: usize,
+        config: &TournamentConfig,
+    ) -> Lattice {
+        let mut rng = config.stream_rng("spatial lattice", 0);
+
+        Lattice {
+            width: spatial.width,
+            height: spatial.height,
+            sites: (0..spatial.width * spatial.height)
+                .map(|_| rng.gen_range(0..num_strategies))
+                .collect(),
+            generation: 0,
+        }
+    }
+
+    /// Number of sites held by every strategy
+    pub fn counts(&self, num_strategies: usize) -> Vec<usize> {
+        let mut counts = vec![0; num_strategies];
+        for &site in &self.sites {
+            counts[site] += 1;
+        }
+        counts
+    }
+
+    /// Points per round of every site, averaged over the matches against its neighbours
+    /// Every match is played by fresh clones, so learners start over against every neighbour and in every generation
+    pub fn scores(
+        &self,
+        strategies: &[NamedStrategy],
+        config: &TournamentConfig,
+        spatial: &SpatialConfig,
+    ) -> Vec<f64> {
+        let mut rng = config.stream_rng("spatial", self.generation);
+        let mut totals = vec![0.0; self.sites.len()];
+        let mut matches = vec![0usize; self.sites.len()];
+
+        for site in 0..self.sites.len() {
+            // every pair of neighbours plays once, both sides keep their own score
+            for neighbour in spatial.neighbours(site).into_iter().filter(|&n| n > site) {
+                let (first_name, first) = &strategies[self.sites[site]];
+                let (second_name, second) = &strategies[self.sites[neighbour]];
+                let mut match_rng = StdRng::seed_from_u64(rng.gen());
+
+                let (GameResult(a, b), history, _) = play_strategies(
+                    &mut first.clone(),
+                    &mut second.clone(),
+                    (Some(first_name), Some(second_name)),
+                    config,
+                    &mut match_rng,
+                );
+                let rounds = history.len().max(1) as f64;
+
+                totals[site] += a / rounds;
+                totals[neighbour] += b / rounds;
+                matches[site] += 1;
+                matches[neighbour] += 1;
+            }
+        }
+
+        totals
+            .into_iter()
+            .zip(matches)
+            .map(|(total, n)| if n > 0 { total / n as f64 } else { 0.0 })
+            .collect()
+    }
+
+    /// Plays one generation: every site adopts the strategy of the best scoring site among itself and its neighbours
+    /// A site only switches when a neighbour did strictly better, so ties keep the current strategy
+    pub fn step(
+        &self,
+        strategies: &[NamedStrategy],
+        config: &TournamentConfig,
+        spatial: &SpatialConfig,
+    ) -> Lattice {
+        let scores = self.scores(strategies, config, spatial);
+
+        let sites = (0..self.sites.len())
+            .map(|site| {
+                let best = spatial.neighbours(site).into_iter().fold(site, |best, n| {
+                    if scores[n] > scores[best] {
+                        n
+                    } else {
+                        best
+                    }
+                });
+                self.sites[best]
+            })
+            .collect();
+
+        Lattice {
+            width: self.width,
+            height: self.height,
+            sites,
+            generation: self.generation + 1,
+        }
+    }
+}
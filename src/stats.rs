@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    strategies::zero_determinant, tournament::TournamentConfig, GameResult, MatchupResult,
+};
+
+/// One value per cell of the strategy grid (row major, NaN for matchups that were not played)
+/// Each value is the mean over repetitions, with its spread kept alongside
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stat {
+    pub values: Vec<f64>,
+    /// Sample standard deviation of each cell, zero with a single repetition
+    pub std_devs: Vec<f64>,
+    /// Half width of the 95% confidence interval of each cell, NaN with a single repetition
+    pub confidence: Vec<f64>,
+    pub strategy_averages: Vec<f64>,
+}
+
+impl Stat {
+    /// Lower and upper bound of the 95% confidence interval of a cell
+    pub fn interval(&self, index: usize) -> (f64, f64) {
+        let mean = self.values[index];
+        let half = self.confidence[index];
+        (mean - half, mean + half)
+    }
+
+    /// Whether a cell differs from `reference` by more than its confidence interval
+    /// Cells without an interval (a single repetition) are always considered significant
+    pub fn is_significant(&self, index: usize, reference: f64) -> bool {
+        let half = self.confidence[index];
+        !half.is_finite() || (self.values[index] - reference).abs() > half
+    }
+
+    /// Mean of every strategy average, the reference the viewer colors against
+    /// Strategies without a single played cell (NaN averages) are left out
+    pub fn overall_average(&self) -> f64 {
+        let played = self.strategy_averages.iter().filter(|v| v.is_finite());
+        played.clone().sum::<f64>() / played.count() as f64
+    }
+}
+
+pub async fn calculate_stats(
+    results: &[MatchupResult],
+    config: &TournamentConfig,
+    grid_width: usize,
+) -> IndexMap<String, Arc<Stat>> {
+    let (point_difference, points_per_round) = tokio::join!(
+        calculate_stat(
+            |result| {
+                result
+                    .results
+                    .iter()
+                    .zip(&result.rounds)
+                    .map(|(GameResult(a, b), rounds)| (a - b) / *rounds as f64)
+                    .collect()
+            },
+            results,
+            config,
+            grid_width
+        ),
+        calculate_stat(
+            |result| {
+                result
+                    .results
+                    .iter()
+                    .zip(&result.rounds)
+                    .map(|(GameResult(a, _), rounds)| a / *rounds as f64)
+                    .collect()
+            },
+            results,
+            config,
+            grid_width
+        )
+    );
+
+    let mut stats = IndexMap::new();
+
+    stats.insert(String::from("Points per round"), Arc::new(points_per_round));
+    // per round like the points, so that matches of different lengths can be compared
+    stats.insert(
+        String::from("Point difference per round"),
+        Arc::new(point_difference),
+    );
+
+    if config.discount_factor().is_some() {
+        let discounted = calculate_stat(
+            |result| {
+                result
+                    .discounted_results
+                    .iter()
+                    .map(|GameResult(a, _)| *a)
+                    .collect()
+            },
+            results,
+            config,
+            grid_width,
+        )
+        .await;
+        stats.insert(
+            String::from("Discounted points per round"),
+            Arc::new(discounted),
+        );
+    }
+
+    // only rows of zero-determinant strategies have a value
+    if results
+        .iter()
+        .any(|result| zero_determinant::find(&result.first_name).is_some())
+    {
+        let payoff = config.payoff;
+        let residual = calculate_stat(
+            |result| {
+                let Some(strategy) = zero_determinant::find(&result.first_name) else {
+                    return vec![];
+                };
+                result
+                    .results
+                    .iter()
+                    .zip(&result.rounds)
+                    .map(|(GameResult(a, b), rounds)| {
+                        let rounds = *rounds as f64;
+                        strategy.residual(&payoff, a / rounds, b / rounds)
+                    })
+                    .collect()
+            },
+            results,
+            config,
+            grid_width,
+        )
+        .await;
+        stats.insert(String::from("ZD relation residual"), Arc::new(residual));
+    }
+
+    stats
+}
+
+/// `by` returns one sample per repetition of a matchup, or none for cells the stat does not apply to
+/// Cells that were not played (the diagonal when self play is off) or without samples are NaN and left out of the averages
+pub async fn calculate_stat(
+    by: impl Fn(&MatchupResult) -> Vec<f64>,
+    results: &[MatchupResult],
+    config: &TournamentConfig,
+    grid_width: usize,
+) -> Stat {
+    let summaries: Vec<_> = (0..grid_width * grid_width)
+        .map(|i| {
+            config
+                .matchup_index(grid_width, i / grid_width, i % grid_width)
+                .map_or((f64::NAN, f64::NAN, f64::NAN), |index| {
+                    summarize(&by(&results[index]))
+                })
+        })
+        .collect();
+
+    let values: Vec<_> = summaries.iter().map(|s| s.0).collect();
+    let std_devs = summaries.iter().map(|s| s.1).collect();
+    let confidence = summaries.iter().map(|s| s.2).collect();
+
+    let strategy_averages = values
+        .chunks_exact(grid_width)
+        .map(|d| {
+            let played = d.iter().filter(|v| v.is_finite());
+            played.clone().sum::<f64>() / played.count() as f64
+        })
+        .collect::<Vec<_>>();
+
+    Stat {
+        values,
+        std_devs,
+        confidence,
+        strategy_averages,
+    }
+}
+
+/// Mean, sample standard deviation and 95% confidence half width of some samples
+pub fn summarize(samples: &[f64]) -> (f64, f64, f64) {
+    let n = samples.len();
+    if n == 0 {
+        return (f64::NAN, f64::NAN, f64::NAN);
+    }
+
+    let mean = samples.iter().sum::<f64>() / n as f64;
+    if n == 1 {
+        return (mean, 0.0, f64::NAN);
+    }
+
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    let std_dev = variance.sqrt();
+
+    (
+        mean,
+        std_dev,
+        t_critical(n - 1) * std_dev / (n as f64).sqrt(),
+    )
+}
+
+/// Two sided 95% critical value of Student's t distribution
+fn t_critical(degrees_of_freedom: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+
+    match degrees_of_freedom {
+        0 => f64::NAN,
+        df if df <= TABLE.len() => TABLE[df - 1],
+        df if df <= 40 => 2.021,
+        df if df <= 60 => 2.000,
+        df if df <= 120 => 1.980,
+        _ => 1.960,
+    }
+}
@@ -0,0 +1,740 @@
+// Entries of Axelrod's computer tournaments, plus the entries of
+// https://plato.stanford.edu/entries/prisoner-dilemma/strategy-table.html that classic.rs skips
+//
+// Axelrod, "Effective Choice in the Prisoner's Dilemma", Journal of Conflict Resolution 24(1) (1980)
+// Axelrod, "More Effective Choice in the Prisoner's Dilemma", Journal of Conflict Resolution 24(3) (1980)
+// Where the published descriptions are ambiguous, these follow the reimplementations of the Axelrod Python library
+// (https://axelrod.readthedocs.io), thresholds are in the points of the original tournaments (R = 3, P = 1, T = 5, S = 0)
+// and rescaled to the tournament's payoff, keeping their place between P and R
+
+use rand::Rng;
+
+use crate::{
+    registry::{Family, Registered, Template},
+    Context, GameHistory, GameMove, MatchInfo, Strategy, COOPERATE, DEFECT,
+};
+
+use super::utils;
+
+pub fn all() -> Vec<Registered> {
+    let entry =
+        |id: &str, name: &str, strategy| Registered::new(id, name, Family::Classic, strategy);
+
+    vec![
+        p_cooperator("cooperator-25", "0.25-Cooperator", &[0.25]),
+        p_cooperator("cooperator-75", "0.75-Cooperator", &[0.75]),
+        entry("gradual", "Gradual", Axelrod::boxed(Gradual::default()))
+            .description("Answers the opponent's n-th defection with n defections, then calms down with two cooperations")
+            .author("Beaufils, Delahaye & Mathieu"),
+        entry(
+            "omega-tit-for-tat",
+            "Omega Tit for Tat",
+            Axelrod::boxed(OmegaTitForTat::default()),
+        )
+        .description("Tit for Tat that breaks deadlocks by cooperating, and defects for good against opponents that look random")
+        .author("Slany & Kienreich"),
+        entry(
+            "tideman-and-chieruzzi",
+            "Tideman and Chieruzzi",
+            Axelrod::boxed(TidemanAndChieruzzi::default()),
+        )
+        .description("Tit for Tat with growing retaliations and fresh starts, defecting in the last two rounds")
+        .author("T. Nicolaus Tideman & Paula Chieruzzi"),
+        entry("nydegger", "Nydegger", Axelrod::boxed(Nydegger))
+            .description("Tit for Tat for three rounds, then a lookup on the outcomes of the last three rounds")
+            .author("Rudy Nydegger")
+            .memory(3),
+        entry("grofman", "Grofman", Axelrod::boxed(Grofman))
+            .description("Cooperates after both players made the same move, and with chance 2/7 otherwise")
+            .author("Bernard Grofman")
+            .stochastic()
+            .memory(1),
+        entry("shubik", "Shubik", Axelrod::boxed(Shubik::default()))
+            .description("Retaliates against every defection that met a cooperation, one round longer each time")
+            .author("Martin Shubik"),
+        entry(
+            "stein-and-rapoport",
+            "Stein and Rapoport",
+            Axelrod::boxed(SteinAndRapoport::default()),
+        )
+        .description("Tit for Tat after four cooperations, defecting for good against opponents that look random and in the last two rounds")
+        .author("William Stein & Amnon Rapoport"),
+        entry("davis", "Davis", Axelrod::boxed(Davis))
+            .description("Cooperates for 10 rounds, then plays Grim")
+            .author("Morton Davis"),
+        entry("graaskamp", "Graaskamp", Axelrod::boxed(Graaskamp::default()))
+            .description("Tit for Tat with a probe in round 51, then defects against random opponents and probes the others every 5 to 15 rounds")
+            .author("Jim Graaskamp")
+            .stochastic(),
+        entry("downing", "Downing", Axelrod::boxed(Downing::default()))
+            .description("Estimates how the opponent answers each of its moves and plays whichever move pays more in the long run")
+            .author("Leslie Downing"),
+        entry("feld", "Feld", Axelrod::boxed(Feld))
+            .description("Tit for Tat that answers cooperations with defections more and more often, up to half the time")
+            .author("Scott Feld")
+            .stochastic(),
+        entry("joss", "Joss", Axelrod::boxed(Joss))
+            .description("Tit for Tat that answers a cooperation with a defection 10% of the time")
+            .author("Johann Joss")
+            .stochastic()
+            .memory(1),
+        entry("tullock", "Tullock", Axelrod::boxed(Tullock))
+            .description("Cooperates for 11 rounds, then 10% less often than the opponent did over the last 10 rounds")
+            .author("Gordon Tullock")
+            .stochastic(),
+        entry("champion", "Champion", Axelrod::boxed(Champion))
+            .description("Cooperates, then plays Tit for Tat, then only sometimes answers defections of uncooperative opponents")
+            .author("Danny Champion")
+            .stochastic(),
+        entry("eatherley", "Eatherley", Axelrod::boxed(Eatherley))
+            .description("Answers a defection with a defection as often as the opponent has defected so far")
+            .author("Graham Eatherley")
+            .stochastic(),
+        entry("tranquilizer", "Tranquilizer", Axelrod::boxed(Tranquilizer))
+            .description("Answers every defection, and slips in single unprovoked defections once it averages enough points")
+            .author("Craig Feathers")
+            .stochastic(),
+    ]
+}
+
+pub fn templates() -> Vec<Template> {
+    vec![Template::new("cooperator", p_cooperator).parameter("p", 0.5, 0.0..=1.0)]
+}
+
+fn p_cooperator(id: &str, name: &str, values: &[f64]) -> Registered {
+    let p = values[0];
+    Registered::new(id, name, Family::Classic, Axelrod::boxed(PCooperator(p)))
+        .description("Cooperates with chance p")
+        .parameter("p", p)
+        .stochastic()
+        .memory(0)
+}
+
+/// Every round so far as pure moves, `true` for a defection
+/// Continuous moves are read once, following `Context::interpretation`, when they are first seen
+#[derive(Debug, Clone, Default)]
+pub struct Record {
+    pub mine: Vec<bool>,
+    pub theirs: Vec<bool>,
+}
+
+impl Record {
+    fn observe(&mut self, history: &GameHistory, round: usize, ctx: &mut Context) {
+        self.mine.push(utils::i_defected(history, round, ctx));
+        self.theirs
+            .push(utils::opponent_defected(history, round, ctx));
+    }
+
+    pub fn len(&self) -> usize {
+        self.theirs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.theirs.is_empty()
+    }
+
+    pub fn my_last(&self) -> Option<bool> {
+        self.mine.last().copied()
+    }
+
+    pub fn their_last(&self) -> Option<bool> {
+        self.theirs.last().copied()
+    }
+
+    pub fn their_defections(&self) -> usize {
+        self.theirs.iter().filter(|&&d| d).count()
+    }
+
+    /// Whether a chi-squared test at the 5% level cannot tell the opponent's moves from fair coin flips
+    pub fn they_look_random(&self) -> bool {
+        // critical value of the chi-squared distribution with one degree of freedom
+        const CRITICAL: f64 = 3.841;
+
+        let n = self.len() as f64;
+        let defections = self.their_defections() as f64;
+        n > 0.0 && (n - 2.0 * defections).powi(2) / n <= CRITICAL
+    }
+
+    /// My points and theirs over the rounds from `start` on
+    pub fn points(&self, start: usize, ctx: &Context) -> (f64, f64) {
+        let to_move = |d: bool| if d { DEFECT } else { COOPERATE };
+
+        self.mine[start..]
+            .iter()
+            .zip(&self.theirs[start..])
+            .map(|(&mine, &theirs)| {
+                let (mine, theirs) = (to_move(mine), to_move(theirs));
+                (
+                    ctx.points(&GameMove(mine, theirs)),
+                    ctx.points(&GameMove(theirs, mine)),
+                )
+            })
+            .fold((0.0, 0.0), |(a, b), (x, y)| (a + x, b + y))
+    }
+}
+
+/// Points per round of the original tournaments placed at the same spot between P and R of the current payoff
+fn rescaled(points: f64, ctx: &Context) -> f64 {
+    let (r, p) = (ctx.payoff.reward, ctx.payoff.punishment);
+    p + (points - 1.0) / 2.0 * (r - p)
+}
+
+/// A lead in points of the original tournaments, in points of the current payoff
+fn rescaled_lead(lead: f64, ctx: &Context) -> f64 {
+    rescaled(lead, ctx) - rescaled(0.0, ctx)
+}
+
+/// Decides whether to defect in the next round
+pub trait Rule: Clone + Send + 'static {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool;
+}
+
+/// Plays a `Rule` with pure moves
+#[derive(Debug, Clone)]
+pub struct Axelrod<R> {
+    record: Record,
+    rule: R,
+    /// The rule as it was before the first match, restored at the start of every match
+    initial: R,
+}
+
+impl<R: Rule> Axelrod<R> {
+    pub fn init(rule: R) -> Self {
+        Axelrod {
+            record: Record::default(),
+            initial: rule.clone(),
+            rule,
+        }
+    }
+
+    fn boxed(rule: R) -> Box<dyn Strategy> {
+        Box::new(Axelrod::init(rule))
+    }
+}
+
+impl<R: Rule> Strategy for Axelrod<R> {
+    fn next_move(&mut self, _: Option<GameMove>, history: &GameHistory, ctx: &mut Context) -> f64 {
+        if let Some(last) = history.len().checked_sub(1) {
+            self.record.observe(history, last, ctx);
+        }
+
+        if self.rule.defect(&self.record, ctx) {
+            DEFECT
+        } else {
+            COOPERATE
+        }
+    }
+
+    fn reset(&mut self) {
+        self.record = Record::default();
+        self.rule = self.initial.clone();
+    }
+
+    /// The rules only remember the current match, so nothing carries over
+    fn on_match_start(&mut self, _: &MatchInfo) {
+        self.reset();
+    }
+}
+
+/// Cooperates with probability `p`
+#[derive(Debug, Clone, Copy)]
+pub struct PCooperator(pub f64);
+
+impl Rule for PCooperator {
+    fn defect(&mut self, _: &Record, ctx: &mut Context) -> bool {
+        ctx.rng.gen::<f64>() >= self.0
+    }
+}
+
+// Beaufils, Delahaye & Mathieu, "Our Meeting With Gradual, A Good Strategy For The Iterated Prisoner's Dilemma" (1996)
+/// Answers the opponent's n-th defection with n defections, then calms down with two cooperations
+#[derive(Debug, Clone, Default)]
+pub struct Gradual {
+    punishing: usize,
+    calming: usize,
+}
+
+impl Rule for Gradual {
+    fn defect(&mut self, record: &Record, _: &mut Context) -> bool {
+        if self.punishing > 0 {
+            self.punishing -= 1;
+            if self.punishing == 0 {
+                self.calming = 2;
+            }
+            return true;
+        }
+        if self.calming > 0 {
+            self.calming -= 1;
+            return false;
+        }
+
+        if record.their_last() == Some(true) {
+            self.punishing = record.their_defections() - 1;
+            if self.punishing == 0 {
+                self.calming = 2;
+            }
+            return true;
+        }
+        false
+    }
+}
+
+// Slany & Kienreich, "On Some Winning Strategies for the Iterated Prisoner's Dilemma" (2007)
+/// Tit for Tat that breaks CD/DC deadlocks by cooperating, and defects for good against opponents that look random
+#[derive(Debug, Clone, Default)]
+pub struct OmegaTitForTat {
+    deadlock: usize,
+    randomness: i32,
+}
+
+impl OmegaTitForTat {
+    const DEADLOCK_THRESHOLD: usize = 3;
+    const RANDOMNESS_THRESHOLD: i32 = 8;
+}
+
+impl Rule for OmegaTitForTat {
+    fn defect(&mut self, record: &Record, _: &mut Context) -> bool {
+        let n = record.len();
+        match n {
+            0 => return false,
+            1 => return record.theirs[0],
+            _ => {}
+        }
+        if self.randomness >= Self::RANDOMNESS_THRESHOLD {
+            return true;
+        }
+
+        let (before, last) = (record.theirs[n - 2], record.theirs[n - 1]);
+        if before != last {
+            self.deadlock += 1;
+        } else {
+            self.deadlock = 0;
+        }
+
+        if self.deadlock >= Self::DEADLOCK_THRESHOLD {
+            self.deadlock = if self.deadlock == Self::DEADLOCK_THRESHOLD {
+                Self::DEADLOCK_THRESHOLD + 1
+            } else {
+                0
+            };
+            return false;
+        }
+
+        if !before && !last {
+            self.randomness -= 1;
+        }
+        // they did not answer my previous move
+        if last != record.mine[n - 2] {
+            self.randomness += 1;
+        }
+        if before != last {
+            self.randomness += 1;
+        }
+
+        self.randomness >= Self::RANDOMNESS_THRESHOLD || last
+    }
+}
+
+// First tournament, 2nd place
+/// Tit for Tat whose retaliation grows by one with every run of the opponent's defections,
+/// offering a fresh start to opponents that are well behind and do not look random, and defecting in the last two rounds
+#[derive(Debug, Clone, Default)]
+pub struct TidemanAndChieruzzi {
+    retaliation: usize,
+    remaining: usize,
+    fresh_start: usize,
+    cooperating: bool,
+}
+
+impl TidemanAndChieruzzi {
+    fn should_start_fresh(&self, record: &Record, ctx: &Context) -> bool {
+        let n = record.len();
+        let rounds = n - self.fresh_start;
+        if rounds < 20 || ctx.rounds.is_some_and(|total| total - n <= 10) {
+            return false;
+        }
+
+        let (mine, theirs) = record.points(self.fresh_start, ctx);
+        let defections = record.theirs[self.fresh_start..]
+            .iter()
+            .filter(|&&d| d)
+            .count() as f64;
+        let std_dev = (rounds as f64 * 0.25).sqrt();
+
+        mine - theirs >= rescaled_lead(10.0, ctx)
+            && (defections - rounds as f64 / 2.0).abs() >= 3.0 * std_dev
+    }
+}
+
+impl Rule for TidemanAndChieruzzi {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        let n = record.len();
+        if n == 0 {
+            return false;
+        }
+        if ctx.rounds.is_some_and(|total| n + 2 >= total) {
+            return true;
+        }
+        if self.cooperating {
+            self.cooperating = false;
+            return false;
+        }
+
+        let new_run = record.theirs[n - 1] && (n == 1 || !record.theirs[n - 2]);
+        if new_run {
+            self.retaliation += 1;
+            self.remaining = self.retaliation;
+        }
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return true;
+        }
+
+        if self.should_start_fresh(record, ctx) {
+            self.fresh_start = n;
+            self.retaliation = 0;
+            self.cooperating = true;
+        }
+        false
+    }
+}
+
+// First tournament, 3rd place
+/// Tit for Tat for three rounds, then a lookup on the outcomes of the last three rounds
+#[derive(Debug, Clone, Copy)]
+pub struct Nydegger;
+
+impl Nydegger {
+    const DEFECT_ON: [usize; 19] = [
+        1, 6, 7, 17, 22, 23, 26, 29, 30, 31, 33, 38, 39, 45, 49, 54, 55, 58, 61,
+    ];
+}
+
+impl Rule for Nydegger {
+    fn defect(&mut self, record: &Record, _: &mut Context) -> bool {
+        let n = record.len();
+        match n {
+            0 => false,
+            1 => record.theirs[0],
+            // only one to cooperate in the first round and only one to defect in the second
+            2 if !record.mine[0] && record.theirs[0] && record.mine[1] && !record.theirs[1] => true,
+            2 => record.theirs[1],
+            _ => {
+                let outcome = |i: usize| match (record.mine[i], record.theirs[i]) {
+                    (false, false) => 0,
+                    (false, true) => 2,
+                    (true, false) => 1,
+                    (true, true) => 3,
+                };
+                let a = 16 * outcome(n - 1) + 4 * outcome(n - 2) + outcome(n - 3);
+                Self::DEFECT_ON.contains(&a)
+            }
+        }
+    }
+}
+
+// First tournament, 4th place
+/// Cooperates after both players made the same move, and with probability 2/7 otherwise
+#[derive(Debug, Clone, Copy)]
+pub struct Grofman;
+
+impl Rule for Grofman {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        match (record.my_last(), record.their_last()) {
+            (Some(mine), Some(theirs)) if mine != theirs => ctx.rng.gen::<f64>() >= 2.0 / 7.0,
+            _ => false,
+        }
+    }
+}
+
+// First tournament, 5th place
+/// Retaliates against every defection that met a cooperation, one round longer each time
+#[derive(Debug, Clone, Default)]
+pub struct Shubik {
+    retaliation: usize,
+    remaining: usize,
+}
+
+impl Rule for Shubik {
+    fn defect(&mut self, record: &Record, _: &mut Context) -> bool {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return true;
+        }
+
+        if record.their_last() == Some(true) && record.my_last() == Some(false) {
+            self.retaliation += 1;
+            self.remaining = self.retaliation - 1;
+            return true;
+        }
+        false
+    }
+}
+
+// First tournament, 6th place
+/// Cooperates for four rounds, then plays Tit for Tat, checking every 15 rounds whether the opponent looks random
+/// and defecting for good once it does, and in the last two rounds
+#[derive(Debug, Clone, Default)]
+pub struct SteinAndRapoport {
+    random_opponent: bool,
+}
+
+impl Rule for SteinAndRapoport {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        let n = record.len();
+        if n < 4 {
+            return false;
+        }
+        if ctx.rounds.is_some_and(|total| n + 2 >= total) {
+            return true;
+        }
+
+        if n % 15 == 14 {
+            self.random_opponent |= record.they_look_random();
+        }
+        self.random_opponent || record.theirs[n - 1]
+    }
+}
+
+// First tournament, 8th place
+/// Cooperates for 10 rounds, then plays Grim
+#[derive(Debug, Clone, Copy)]
+pub struct Davis;
+
+impl Rule for Davis {
+    fn defect(&mut self, record: &Record, _: &mut Context) -> bool {
+        record.len() >= 10 && record.their_defections() > 0
+    }
+}
+
+// First tournament, 9th place
+/// Tit for Tat with a single probing defection in round 51, then from round 57 on defects for good against opponents
+/// that look random, plays Tit for Tat against opponents that copy it and slips a defection in every 5 to 15 rounds against the rest
+#[derive(Debug, Clone, Default)]
+pub struct Graaskamp {
+    random_opponent: bool,
+    next_probe: Option<usize>,
+}
+
+impl Rule for Graaskamp {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        let n = record.len();
+        if n == 0 {
+            return false;
+        }
+        if n < 56 {
+            return n == 50 || record.theirs[n - 1];
+        }
+
+        self.random_opponent |= record.they_look_random();
+        if self.random_opponent {
+            return true;
+        }
+
+        let copies_me = (1..n).all(|i| record.theirs[i] == record.mine[i - 1]);
+        if copies_me || record.theirs == record.mine {
+            return record.theirs[n - 1];
+        }
+
+        let probe = *self
+            .next_probe
+            .get_or_insert_with(|| n + ctx.rng.gen_range(5..=15));
+        if n == probe {
+            self.next_probe = Some(n + ctx.rng.gen_range(5..=15));
+            return true;
+        }
+        false
+    }
+}
+
+// First tournament, 10th place
+/// Estimates how often the opponent cooperates after each of its own moves and plays the move with the higher expected payoff,
+/// alternating on ties, after defecting in the first two rounds
+#[derive(Debug, Clone, Default)]
+pub struct Downing {
+    /// Times the opponent cooperated right after I cooperated and right after I defected
+    answered_cooperation: usize,
+    answered_defection: usize,
+}
+
+impl Rule for Downing {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        let n = record.len();
+        if n < 2 {
+            return true;
+        }
+        if !record.theirs[n - 1] {
+            if record.mine[n - 2] {
+                self.answered_defection += 1;
+            } else {
+                self.answered_cooperation += 1;
+            }
+        }
+
+        let defections = record.mine.iter().filter(|&&d| d).count();
+        let cooperations = n - defections;
+        // starts out assuming one cooperation, so that an opponent that has never been cooperated with is not trusted
+        let alpha = self.answered_cooperation as f64 / (cooperations + 1) as f64;
+        let beta = self.answered_defection as f64 / defections.max(2) as f64;
+
+        let payoff = &ctx.payoff;
+        let cooperating = alpha * payoff.reward + (1.0 - alpha) * payoff.sucker;
+        let defecting = beta * payoff.temptation + (1.0 - beta) * payoff.punishment;
+        if cooperating == defecting {
+            !record.mine[n - 1]
+        } else {
+            defecting > cooperating
+        }
+    }
+}
+
+// First tournament, 11th place
+/// Tit for Tat, but the chance of answering a cooperation with a defection grows from 0 to 1/2 over 200 rounds
+#[derive(Debug, Clone, Copy)]
+pub struct Feld;
+
+impl Rule for Feld {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        match record.their_last() {
+            None => false,
+            Some(true) => true,
+            Some(false) => ctx.rng.gen::<f64>() < 0.5 * (record.len() as f64 / 200.0).min(1.0),
+        }
+    }
+}
+
+// First tournament, 12th place
+/// Tit for Tat that answers a cooperation with a defection 10% of the time
+#[derive(Debug, Clone, Copy)]
+pub struct Joss;
+
+impl Rule for Joss {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        match record.their_last() {
+            None => false,
+            Some(true) => true,
+            Some(false) => ctx.rng.gen::<f64>() < 0.1,
+        }
+    }
+}
+
+// First tournament, 13th place
+/// Cooperates for 11 rounds, then 10% less often than the opponent did over the last 10 rounds
+#[derive(Debug, Clone, Copy)]
+pub struct Tullock;
+
+impl Rule for Tullock {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        let n = record.len();
+        if n < 11 {
+            return false;
+        }
+
+        let cooperations = record.theirs[n - 10..].iter().filter(|&&d| !d).count();
+        let cooperation = (cooperations as f64 / 10.0 - 0.1).max(0.0);
+        ctx.rng.gen::<f64>() >= cooperation
+    }
+}
+
+// Second tournament entry
+/// Cooperates for 10 rounds, plays Tit for Tat until round 25,
+/// then only answers defections of opponents that cooperated less than 60% of the time, and randomly
+#[derive(Debug, Clone, Copy)]
+pub struct Champion;
+
+impl Rule for Champion {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        let n = record.len();
+        if n < 10 {
+            return false;
+        }
+        if n < 25 {
+            return record.theirs[n - 1];
+        }
+
+        let cooperation = 1.0 - record.their_defections() as f64 / n as f64;
+        record.theirs[n - 1] && cooperation < 0.6 && ctx.rng.gen::<f64>() > cooperation
+    }
+}
+
+// Second tournament entry
+/// Answers a defection with a defection as often as the opponent has defected so far
+#[derive(Debug, Clone, Copy)]
+pub struct Eatherley;
+
+impl Rule for Eatherley {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        match record.their_last() {
+            Some(true) => {
+                ctx.rng.gen::<f64>() < record.their_defections() as f64 / record.len() as f64
+            }
+            _ => false,
+        }
+    }
+}
+
+// Second tournament entry, simplified from Craig Feathers' description
+/// Answers every defection, and once it averages enough points slips in single unprovoked defections,
+/// more often the better it does
+#[derive(Debug, Clone, Copy)]
+pub struct Tranquilizer;
+
+impl Rule for Tranquilizer {
+    fn defect(&mut self, record: &Record, ctx: &mut Context) -> bool {
+        match (record.my_last(), record.their_last()) {
+            (_, None) => return false,
+            (_, Some(true)) => return true,
+            (Some(true), _) => return false,
+            _ => {}
+        }
+
+        let (low, high) = (rescaled(1.75, ctx), rescaled(2.25, ctx));
+
+        let average = record.points(0, ctx).0 / record.len() as f64;
+        let chance = if average < low {
+            0.0
+        } else if average < high {
+            0.1
+        } else {
+            0.25
+        };
+        ctx.rng.gen::<f64>() < chance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{play_matchup, registry, tournament::TournamentConfig, MatchupResult};
+
+    fn carry_over(first: &str, second: &str) -> MatchupResult {
+        let config = TournamentConfig {
+            rounds: 30,
+            repetitions: 2,
+            seed: Some(0),
+            carry_over: true,
+            ..Default::default()
+        };
+        let strategy = |id: &str| registry::resolve(id).unwrap().strategy;
+
+        play_matchup(
+            first.to_string(),
+            strategy(first),
+            second.to_string(),
+            strategy(second),
+            &config,
+        )
+    }
+
+    #[test]
+    fn rules_start_every_match_afresh_when_carrying_over() {
+        for id in [
+            "tideman-and-chieruzzi",
+            "stein-and-rapoport",
+            "gradual",
+            "shubik",
+        ] {
+            let result = carry_over(id, "classic-tit-for-tat");
+            assert_eq!(result.results[0], result.results[1], "{}", id);
+        }
+    }
+}
@@ -1,49 +1,131 @@
 // Implementations of https://plato.stanford.edu/entries/prisoner-dilemma/strategy-table.html
 
-use crate::{from_functional, GameHistory, GameMove, Strategy, COOPERATE, DEFECT, P, R, S, T};
+use rand::Rng;
+
+use crate::{
+    from_contextual, from_functional,
+    registry::{Family, Registered, Template},
+    Context, GameHistory, GameMove, MatchInfo, Strategy, COOPERATE, DEFECT,
+};
 
 use super::utils;
 
-pub fn all() -> Vec<(&'static str, Box<dyn Strategy>)> {
+pub fn all() -> Vec<Registered> {
     vec![
-        (
+        Registered::new(
+            "classic-unconditional-cooperator",
             "Classic Unconditional Cooperator",
+            Family::Classic,
             from_functional(unconditional_cooperator),
-        ),
-        (
+        )
+        .description("Always cooperates")
+        .memory(0),
+        Registered::new(
+            "classic-unconditional-defector",
             "Classic Unconditional Defector",
+            Family::Classic,
             from_functional(unconditional_defector),
-        ),
-        ("Classic Random", from_functional(random)),
-        ("Classic Tit for Tat", from_functional(tit_for_tat)),
-        (
+        )
+        .description("Always defects")
+        .memory(0),
+        Registered::new(
+            "classic-random",
+            "Classic Random",
+            Family::Classic,
+            from_contextual(random),
+        )
+        .description("Cooperates or defects with equal chance")
+        .stochastic()
+        .memory(0),
+        Registered::new(
+            "classic-tit-for-tat",
+            "Classic Tit for Tat",
+            Family::Classic,
+            from_contextual(tit_for_tat),
+        )
+        .description("Cooperates first, then copies the opponent's last move")
+        .author("Anatol Rapoport")
+        .memory(1),
+        Registered::new(
+            "classic-suspicious-tit-for-tat",
             "Classic Suspicious Tit for Tat",
-            from_functional(suspicious_tit_for_tat),
-        ),
-        (
+            Family::Classic,
+            from_contextual(suspicious_tit_for_tat),
+        )
+        .description("Defects first, then copies the opponent's last move")
+        .memory(1),
+        Registered::new(
+            "classic-generous-tit-for-tat",
             "Classic Generous Tit for Tat",
-            from_functional(generous_tit_for_tat),
-        ),
-        (
+            Family::Classic,
+            from_contextual(generous_tit_for_tat),
+        )
+        .description("Copies the opponent's last move, but forgives a defection with a chance derived from the payoff")
+        .author("Nowak & Sigmund")
+        .stochastic()
+        .memory(1),
+        Registered::new(
+            "classic-imperfect-tit-for-tat",
             "Classic Imperfect Tit for Tat",
-            from_functional(imperfect_tit_for_tat),
-        ),
-        (
+            Family::Classic,
+            from_contextual(imperfect_tit_for_tat),
+        )
+        .description("Copies the opponent's last move 95% of the time, and plays its opposite otherwise")
+        .stochastic()
+        .memory(1),
+        Registered::new(
+            "classic-tit-for-two-tats",
             "Classic Tit for Two Tats",
-            from_functional(tit_for_two_tats),
-        ),
-        (
+            Family::Classic,
+            from_contextual(tit_for_two_tats),
+        )
+        .description("Defects only after the opponent defected twice in a row")
+        .author("John Maynard Smith")
+        .memory(2),
+        Registered::new(
+            "classic-two-tits-for-tat",
             "Classic Two Tits for Tat",
-            from_functional(two_tits_for_tat),
-        ),
-        ("Classic Grim", from_functional(grim)),
-        ("Classic Pavlov", from_functional(pavlov)),
-        ("Classic 2Pavlov", Box::new(NPavlov::init(2.0))),
-        ("Classic 4Pavlov", Box::new(NPavlov::init(4.0))),
-        ("Classic 8Pavlov", Box::new(NPavlov::init(8.0))),
+            Family::Classic,
+            from_contextual(two_tits_for_tat),
+        )
+        .description("Defects whenever the opponent defected in either of the last two rounds")
+        .memory(2),
+        Registered::new(
+            "classic-grim",
+            "Classic Grim",
+            Family::Classic,
+            from_contextual(grim),
+        )
+        .description("Cooperates until the opponent defects once, then defects for good")
+        .author("James W. Friedman"),
+        Registered::new(
+            "classic-pavlov",
+            "Classic Pavlov",
+            Family::Classic,
+            from_contextual(pavlov),
+        )
+        .description("Win-stay lose-shift: keeps its move after R or T, switches after P or S")
+        .author("Kraines & Kraines")
+        .memory(1),
+        npavlov("classic-2pavlov", "Classic 2Pavlov", &[2.0]),
+        npavlov("classic-4pavlov", "Classic 4Pavlov", &[4.0]),
+        npavlov("classic-8pavlov", "Classic 8Pavlov", &[8.0]),
     ]
 }
 
+pub fn templates() -> Vec<Template> {
+    vec![Template::new("classic-npavlov", npavlov).integer_parameter("n", 4, 1..=1000)]
+}
+
+fn npavlov(id: &str, name: &str, values: &[f64]) -> Registered {
+    let n = values[0];
+    Registered::new(id, name, Family::Classic, Box::new(NPavlov::init(n)))
+        .description("Cooperates with chance p, which moves by 1/n towards cooperating after R or P and away after T or S")
+        .author("Kraines & Kraines")
+        .parameter("n", n)
+        .stochastic()
+}
+
 pub fn unconditional_cooperator(_: &GameHistory) -> f64 {
     COOPERATE
 }
@@ -52,102 +134,60 @@ pub fn unconditional_defector(_: &GameHistory) -> f64 {
     DEFECT
 }
 
-pub fn random(_: &GameHistory) -> f64 {
-    if rand::random::<bool>() {
+pub fn random(_: &GameHistory, ctx: &mut Context) -> f64 {
+    if ctx.rng.gen::<bool>() {
         COOPERATE
     } else {
         DEFECT
     }
 }
 
-// skip p_cooperator
+// p-Cooperator is in axelrod.rs
 
-pub fn tit_for_tat(history: &GameHistory) -> f64 {
-    history
-        .last()
-        .map(utils::to_opponent_move)
-        .map_or(COOPERATE, utils::to_nearest_move)
+pub fn tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
+    utils::read_last_opponent_move(history, ctx).unwrap_or(COOPERATE)
 }
 
-pub fn suspicious_tit_for_tat(history: &GameHistory) -> f64 {
-    history
-        .last()
-        .map(utils::to_opponent_move)
-        .map_or(DEFECT, utils::to_nearest_move)
+pub fn suspicious_tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
+    utils::read_last_opponent_move(history, ctx).unwrap_or(DEFECT)
 }
 
-pub fn generous_tit_for_tat(history: &GameHistory) -> f64 {
-    history
-        .last()
-        .map(utils::to_opponent_move)
-        .map_or(DEFECT, |opponent_move| {
-            let opponent_move = utils::to_nearest_move(opponent_move);
-
-            if utils::is_defection(&opponent_move) {
-                let g: f64 = (1.0 - ((T - R) / (R - S))).min((R - P) / (T - P));
-                if rand::random::<f64>() < g {
-                    COOPERATE
-                } else {
-                    opponent_move
-                }
+pub fn generous_tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
+    utils::read_last_opponent_move(history, ctx).map_or(DEFECT, |opponent_move| {
+        if opponent_move == DEFECT {
+            let g = ctx.payoff.generosity();
+            if ctx.rng.gen::<f64>() < g {
+                COOPERATE
             } else {
                 opponent_move
             }
-        })
-}
-
-// skipping gradual tit for tat
-//    pub fn gradual_tit_for_tat(history: GameHistory) -> f64 {
-//        if history.is_empty() {
-//            return COOPERATE;
-//        }
-//
-//        let opponent_defections = history
-//            .iter()
-//            .map(|&(_, opponent_move)| opponent_move)
-//            .filter(|m| m < &DEFECTION_THRESHOLD)
-//            .count();
-//
-//        let last_string = history
-//            .iter()
-//            .rev()
-//            .map(|&(my_move, _)| my_move)
-//            .skip_while(|m| m > &DEFECTION_THRESHOLD)
-//            .take_while(|m| m < &DEFECTION_THRESHOLD)
-//            .count();
-//
-//        if opponent_defections <= last_string {
-//            cont...
-//        }
-//    }
-
-pub fn imperfect_tit_for_tat(history: &GameHistory) -> f64 {
-    const ACCURACY: f64 = 0.95;
+        } else {
+            opponent_move
+        }
+    })
+}
 
-    history
-        .last()
-        .map(utils::to_opponent_move)
-        .map_or(COOPERATE, |opponent_move| {
-            let opponent_move = utils::to_nearest_move(opponent_move);
+// Gradual Tit for Tat is in axelrod.rs
 
-            if rand::random::<f64>() < ACCURACY {
-                opponent_move
-            } else {
-                utils::to_opposite(opponent_move)
-            }
-        })
+pub fn imperfect_tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
+    const ACCURACY: f64 = 0.95;
+
+    utils::read_last_opponent_move(history, ctx).map_or(COOPERATE, |opponent_move| {
+        if ctx.rng.gen::<f64>() < ACCURACY {
+            opponent_move
+        } else {
+            utils::to_opposite(opponent_move)
+        }
+    })
 }
 
-pub fn tit_for_two_tats(history: &GameHistory) -> f64 {
-    if history.len() <= 2 {
+pub fn tit_for_two_tats(history: &GameHistory, ctx: &mut Context) -> f64 {
+    if history.len() < 2 {
         COOPERATE
     } else {
-        let two_defections = history
-            .iter()
+        let two_defections = (history.len() - 2..history.len())
             .rev()
-            .take(2)
-            .map(utils::to_opponent_move)
-            .filter(utils::is_defection)
+            .filter(|&round| utils::opponent_defected(history, round, ctx))
             .count()
             == 2;
 
@@ -159,32 +199,22 @@ pub fn tit_for_two_tats(history: &GameHistory) -> f64 {
     }
 }
 
-pub fn two_tits_for_tat(history: &GameHistory) -> f64 {
-    if history.len() <= 2 {
-        COOPERATE
-    } else {
-        let any_defections = history
-            .iter()
-            .rev()
-            .take(2)
-            .map(utils::to_opponent_move)
-            .any(|m| utils::is_defection(&m));
+pub fn two_tits_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
+    let any_defections = (history.len().saturating_sub(2)..history.len())
+        .rev()
+        .any(|round| utils::opponent_defected(history, round, ctx));
 
-        if any_defections {
-            DEFECT
-        } else {
-            COOPERATE
-        }
+    if any_defections {
+        DEFECT
+    } else {
+        COOPERATE
     }
 }
 
-// skipped omega_tit_for_tat
+// Omega Tit for Tat is in axelrod.rs
 
-pub fn grim(history: &GameHistory) -> f64 {
-    let any_defections = history
-        .iter()
-        .map(utils::to_opponent_move)
-        .any(|m| utils::is_defection(&m));
+pub fn grim(history: &GameHistory, ctx: &mut Context) -> f64 {
+    let any_defections = utils::read_opponent_moves(history, ctx).any(|defected| defected);
 
     if any_defections {
         DEFECT
@@ -193,13 +223,16 @@ pub fn grim(history: &GameHistory) -> f64 {
     }
 }
 
-pub fn pavlov(history: &GameHistory) -> f64 {
-    history.last().map_or(COOPERATE, |GameMove(m, o)| {
-        match (utils::is_defection(m), utils::is_defection(o)) {
-            (false, false) | (true, false) => *m,                   // R, T
-            (false, true) | (true, true) => utils::to_opposite(*m), // P, S
-        }
-    })
+pub fn pavlov(history: &GameHistory, ctx: &mut Context) -> f64 {
+    let Some(last) = history.len().checked_sub(1) else {
+        return COOPERATE;
+    };
+
+    let m = utils::i_defected(history, last, ctx);
+    match (m, utils::opponent_defected(history, last, ctx)) {
+        (false, false) | (true, false) => utils::to_move(m), // R, T
+        (false, true) | (true, true) => utils::to_move(!m),  // P, S
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -216,19 +249,110 @@ impl NPavlov {
 
 impl Strategy for NPavlov {
     // unsure if https://plato.stanford.edu/entries/prisoner-dilemma/strategy-table.html has the right implementation?
-    fn next_move(&mut self, last_move: Option<GameMove>, _history: &GameHistory) -> f64 {
-        self.p += last_move.map_or(COOPERATE, |GameMove(m, o)| {
-            match (utils::is_defection(&m), utils::is_defection(&o)) {
+    fn next_move(
+        &mut self,
+        _last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        self.p += history.len().checked_sub(1).map_or(COOPERATE, |last| {
+            match (
+                utils::i_defected(history, last, ctx),
+                utils::opponent_defected(history, last, ctx),
+            ) {
                 (false, false) | (true, true) => 1.0 / self.n,  // R, P
                 (false, true) | (true, false) => -1.0 / self.n, // T, S
             }
         });
         self.p = self.p.clamp(0.0, 1.0);
 
-        if self.p < rand::random() {
+        // p is the chance of cooperating, as in the continuous NPavlov
+        if ctx.rng.gen::<f64>() < self.p {
             COOPERATE
         } else {
             DEFECT
         }
     }
+
+    fn reset(&mut self) {
+        self.p = 1.0;
+    }
+
+    /// p follows the current opponent, so every match starts from cooperating
+    fn on_match_start(&mut self, _: &MatchInfo) {
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{interpolation::Bilinear, interpretation::Interpretation, payoff::Payoff};
+
+    fn context(seed: u64) -> Context {
+        Context::new(
+            Payoff::default(),
+            Arc::new(Bilinear),
+            Interpretation::default(),
+            None,
+            StdRng::seed_from_u64(seed),
+        )
+    }
+
+    fn history(theirs: &[f64]) -> GameHistory {
+        theirs.iter().map(|&m| GameMove(COOPERATE, m)).collect()
+    }
+
+    #[test]
+    fn tit_for_two_tats_answers_the_first_two_defections() {
+        let mut ctx = context(0);
+
+        assert_eq!(tit_for_two_tats(&history(&[]), &mut ctx), COOPERATE);
+        assert_eq!(tit_for_two_tats(&history(&[DEFECT]), &mut ctx), COOPERATE);
+        assert_eq!(
+            tit_for_two_tats(&history(&[DEFECT, DEFECT]), &mut ctx),
+            DEFECT
+        );
+        assert_eq!(
+            tit_for_two_tats(&history(&[COOPERATE, DEFECT]), &mut ctx),
+            COOPERATE
+        );
+    }
+
+    #[test]
+    fn two_tits_for_tat_answers_the_first_defection() {
+        let mut ctx = context(0);
+
+        assert_eq!(two_tits_for_tat(&history(&[]), &mut ctx), COOPERATE);
+        assert_eq!(two_tits_for_tat(&history(&[DEFECT]), &mut ctx), DEFECT);
+        assert_eq!(
+            two_tits_for_tat(&history(&[DEFECT, COOPERATE]), &mut ctx),
+            DEFECT
+        );
+        assert_eq!(
+            two_tits_for_tat(&history(&[DEFECT, COOPERATE, COOPERATE]), &mut ctx),
+            COOPERATE
+        );
+    }
+
+    #[test]
+    fn npavlov_plays_p_as_its_chance_of_cooperating() {
+        for seed in 0..100 {
+            let mut ctx = context(seed);
+            let mut npavlov = NPavlov::init(1.0);
+
+            // p starts at 1
+            assert_eq!(npavlov.next_move(None, &history(&[]), &mut ctx), COOPERATE);
+            // and drops to 0 after S
+            let sucker = history(&[DEFECT]);
+            assert_eq!(
+                npavlov.next_move(sucker.last().cloned(), &sucker, &mut ctx),
+                DEFECT
+            );
+        }
+    }
 }
@@ -0,0 +1,590 @@
+// Strategies built out of other strategies, so families of variants can be entered as specs
+// such as `noisy(tit-for-tat, sigma=0.1)` or `mixture(0.7 * tit-for-tat, 0.3 * grim)` without a file of their own
+
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+use crate::{
+    registry::{Argument, Family, Memory, Registered, Template},
+    Context, GameHistory, GameMove, GameResult, MatchInfo, Strategy, COOPERATE, DEFECT,
+};
+
+pub fn templates() -> Vec<Template> {
+    vec![
+        Template::combinator("noisy", "strategy", |id, name, arguments, values| {
+            let [inner] = single(arguments, "noisy")?;
+            let sigma = values[0];
+            Ok(wrap(
+                id,
+                name,
+                &inner,
+                Box::new(Noisy::init(inner.strategy.clone(), sigma)),
+                format!(
+                    "{} with gaussian noise of standard deviation {} on its moves",
+                    inner.info.name, sigma
+                ),
+            )
+            .parameter("sigma", sigma)
+            .stochastic())
+        })
+        .parameter("sigma", 0.1, 0.0..=1.0),
+        mixture("mixture", false),
+        mixture("mixture-sample", true),
+        Template::combinator(
+            "switch",
+            "strategy, strategy",
+            |id, name, arguments, values| {
+                let [first, second] = pair(arguments, "switch")?;
+                let after = values[0];
+
+                let registered = Registered::new(
+                    id,
+                    name,
+                    Family::Custom,
+                    Box::new(Switch::init(
+                        first.strategy.strategy,
+                        second.strategy.strategy,
+                        after as usize,
+                    )),
+                )
+                .description(&format!(
+                    "{} for the first {} rounds, then {}",
+                    first.strategy.info.name, after, second.strategy.info.name
+                ))
+                .parameter("after", after);
+
+                Ok(
+                    if first.strategy.info.stochastic || second.strategy.info.stochastic {
+                        registered.stochastic()
+                    } else {
+                        registered
+                    },
+                )
+            },
+        )
+        .integer_parameter("after", 100, 0..=1_000_000),
+        Template::combinator("invert", "strategy", |id, name, arguments, _| {
+            let [inner] = single(arguments, "invert")?;
+            Ok(wrap(
+                id,
+                name,
+                &inner,
+                Box::new(Invert::init(inner.strategy.clone())),
+                format!(
+                    "{} with every move turned into its opposite",
+                    inner.info.name
+                ),
+            ))
+        }),
+        Template::combinator("clamp", "strategy", |id, name, arguments, values| {
+            let [inner] = single(arguments, "clamp")?;
+            let (lo, hi) = (values[0], values[1]);
+            if lo > hi {
+                return Err(format!(
+                    "parameter 'lo' of clamp must not be above hi, got {} > {}",
+                    lo, hi
+                ));
+            }
+
+            Ok(wrap(
+                id,
+                name,
+                &inner,
+                Box::new(Clamp::init(inner.strategy.clone(), lo, hi)),
+                format!(
+                    "{} with its moves kept between {} and {}",
+                    inner.info.name, lo, hi
+                ),
+            )
+            .parameter("lo", lo)
+            .parameter("hi", hi))
+        })
+        .parameter("lo", COOPERATE, COOPERATE..=DEFECT)
+        .parameter("hi", DEFECT, COOPERATE..=DEFECT),
+        Template::combinator("quantize", "strategy", |id, name, arguments, values| {
+            let [inner] = single(arguments, "quantize")?;
+            let levels = values[0];
+
+            Ok(wrap(
+                id,
+                name,
+                &inner,
+                Box::new(Quantize::init(inner.strategy.clone(), levels as usize)),
+                format!(
+                    "{} with its moves rounded to {} evenly spaced values",
+                    inner.info.name, levels
+                ),
+            )
+            .parameter("levels", levels))
+        })
+        .integer_parameter("levels", 2, 2..=101),
+    ]
+}
+
+/// Specs registered as strategies of their own, the examples of the README, named and identified as if built from the spec
+pub fn presets() -> Vec<&'static str> {
+    vec![
+        "noisy(tit-for-tat, sigma=0.1)",
+        "mixture(0.7 * tit-for-tat, 0.3 * grim)",
+        "switch(grim, pavlov, after=50)",
+    ]
+}
+
+/// `mixture`, or with `sample` the variant that plays the move of a single part every round
+fn mixture(id: &'static str, sample: bool) -> Template {
+    Template::combinator(
+        id,
+        "weight * strategy, weight * strategy, ...",
+        move |variant_id, name, arguments, _| {
+            if arguments.len() < 2 {
+                return Err(format!(
+                    "{} takes at least 2 strategies, got {}",
+                    id,
+                    arguments.len()
+                ));
+            }
+            let weights: Vec<_> = arguments.iter().map(|a| a.weight.unwrap_or(1.0)).collect();
+            if weights.iter().any(|&w| w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+                return Err(format!(
+                    "weights of {} must not be negative, and at least one must be positive",
+                    id
+                ));
+            }
+
+            let names = arguments
+                .iter()
+                .map(|a| a.strategy.info.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let description = if sample {
+                format!(
+                    "Plays the move of one of {} every round, picked by weight",
+                    names
+                )
+            } else {
+                format!("Weighted average of the moves of {}", names)
+            };
+            let stochastic = sample || arguments.iter().any(|a| a.strategy.info.stochastic);
+            let memory = longest_memory(&arguments);
+
+            let parts = arguments
+                .into_iter()
+                .zip(weights)
+                .map(|(argument, weight)| (argument.strategy.strategy, weight))
+                .collect();
+            let registered = Registered::new(
+                variant_id,
+                name,
+                Family::Custom,
+                Box::new(Mixture::init(parts, sample)),
+            )
+            .description(&description);
+
+            Ok(with_memory(
+                if stochastic {
+                    registered.stochastic()
+                } else {
+                    registered
+                },
+                memory,
+            ))
+        },
+    )
+}
+
+/// The only strategy of a combinator that takes one, without a weight
+fn single(arguments: Vec<Argument>, id: &str) -> Result<[Registered; 1], String> {
+    let [argument] = unweighted(arguments, id)?;
+    Ok([argument.strategy])
+}
+
+/// Both strategies of a combinator that takes two, without weights
+fn pair(arguments: Vec<Argument>, id: &str) -> Result<[Argument; 2], String> {
+    unweighted(arguments, id)
+}
+
+fn unweighted<const N: usize>(arguments: Vec<Argument>, id: &str) -> Result<[Argument; N], String> {
+    if let Some(argument) = arguments.iter().find(|a| a.weight.is_some()) {
+        return Err(format!(
+            "only mixture and mixture-sample take weights, {} was given one for '{}'",
+            id, argument.strategy.info.id
+        ));
+    }
+    let count = arguments.len();
+    arguments.try_into().map_err(|_| {
+        format!(
+            "{} takes {} {}, got {}",
+            id,
+            N,
+            if N == 1 { "strategy" } else { "strategies" },
+            count
+        )
+    })
+}
+
+/// A wrapper of a single strategy, which keeps its memory and whether it is stochastic
+fn wrap(
+    id: &str,
+    name: &str,
+    inner: &Registered,
+    strategy: Box<dyn Strategy>,
+    description: String,
+) -> Registered {
+    let registered = Registered::new(id, name, Family::Custom, strategy).description(&description);
+    let registered = if inner.info.stochastic {
+        registered.stochastic()
+    } else {
+        registered
+    };
+    with_memory(registered, inner.info.memory)
+}
+
+fn with_memory(registered: Registered, memory: Memory) -> Registered {
+    match memory {
+        Memory::Rounds(rounds) => registered.memory(rounds),
+        Memory::Full => registered,
+    }
+}
+
+fn longest_memory(arguments: &[Argument]) -> Memory {
+    arguments
+        .iter()
+        .map(|a| a.strategy.info.memory)
+        .fold(Memory::Rounds(0), Memory::longest)
+}
+
+/// Adds gaussian noise with standard deviation `sigma` to every move of `inner`, drawn from the player's `Context`
+#[derive(Clone)]
+pub struct Noisy {
+    inner: Box<dyn Strategy>,
+    sigma: f64,
+}
+
+impl Noisy {
+    pub fn init(inner: Box<dyn Strategy>, sigma: f64) -> Self {
+        Noisy { inner, sigma }
+    }
+}
+
+impl Strategy for Noisy {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        let m = self.inner.next_move(last_move, history, ctx);
+        let noise = Normal::new(0.0, self.sigma).map_or(0.0, |normal| normal.sample(&mut ctx.rng));
+        (m + noise).clamp(COOPERATE, DEFECT)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn on_match_start(&mut self, info: &MatchInfo) {
+        self.inner.on_match_start(info);
+    }
+
+    fn on_match_end(&mut self, result: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        self.inner.on_match_end(result, history, ctx);
+    }
+}
+
+/// Every part plays every round, so stateful parts keep up with the match
+/// Plays the weighted average of their moves, or with `sample` the move of a single part picked by weight
+#[derive(Clone)]
+pub struct Mixture {
+    parts: Vec<(Box<dyn Strategy>, f64)>,
+    sample: bool,
+}
+
+impl Mixture {
+    /// Weights need not add up to 1
+    pub fn init(parts: Vec<(Box<dyn Strategy>, f64)>, sample: bool) -> Self {
+        Mixture { parts, sample }
+    }
+}
+
+impl Strategy for Mixture {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        let moves: Vec<_> = self
+            .parts
+            .iter_mut()
+            .map(|(part, weight)| (part.next_move(last_move.clone(), history, ctx), *weight))
+            .collect();
+        let total: f64 = moves.iter().map(|(_, weight)| weight).sum();
+
+        if self.sample {
+            let mut pick = ctx.rng.gen::<f64>() * total;
+            for &(m, weight) in &moves {
+                if pick < weight {
+                    return m;
+                }
+                pick -= weight;
+            }
+            moves.last().map_or(COOPERATE, |&(m, _)| m)
+        } else {
+            moves.iter().map(|(m, weight)| m * weight).sum::<f64>() / total
+        }
+    }
+
+    fn reset(&mut self) {
+        self.parts.iter_mut().for_each(|(part, _)| part.reset());
+    }
+
+    fn on_match_start(&mut self, info: &MatchInfo) {
+        self.parts
+            .iter_mut()
+            .for_each(|(part, _)| part.on_match_start(info));
+    }
+
+    fn on_match_end(&mut self, result: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        self.parts
+            .iter_mut()
+            .for_each(|(part, _)| part.on_match_end(result, history, ctx));
+    }
+}
+
+/// Plays `first` for the first `after` rounds, then `second`
+/// Both play every round and the move of the other one is discarded, so stateful strategies keep up with the match
+#[derive(Clone)]
+pub struct Switch {
+    first: Box<dyn Strategy>,
+    second: Box<dyn Strategy>,
+    after: usize,
+}
+
+impl Switch {
+    pub fn init(first: Box<dyn Strategy>, second: Box<dyn Strategy>, after: usize) -> Self {
+        Switch {
+            first,
+            second,
+            after,
+        }
+    }
+}
+
+impl Strategy for Switch {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        let first = self.first.next_move(last_move.clone(), history, ctx);
+        let second = self.second.next_move(last_move, history, ctx);
+
+        if history.len() < self.after {
+            first
+        } else {
+            second
+        }
+    }
+
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+    }
+
+    fn on_match_start(&mut self, info: &MatchInfo) {
+        self.first.on_match_start(info);
+        self.second.on_match_start(info);
+    }
+
+    fn on_match_end(&mut self, result: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        self.first.on_match_end(result, history, ctx);
+        self.second.on_match_end(result, history, ctx);
+    }
+}
+
+/// Plays the opposite of every move of `inner`
+#[derive(Clone)]
+pub struct Invert {
+    inner: Box<dyn Strategy>,
+}
+
+impl Invert {
+    pub fn init(inner: Box<dyn Strategy>) -> Self {
+        Invert { inner }
+    }
+}
+
+impl Strategy for Invert {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        DEFECT
+            - self
+                .inner
+                .next_move(last_move, history, ctx)
+                .clamp(COOPERATE, DEFECT)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn on_match_start(&mut self, info: &MatchInfo) {
+        self.inner.on_match_start(info);
+    }
+
+    fn on_match_end(&mut self, result: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        self.inner.on_match_end(result, history, ctx);
+    }
+}
+
+/// Keeps every move of `inner` between `lo` and `hi`
+#[derive(Clone)]
+pub struct Clamp {
+    inner: Box<dyn Strategy>,
+    lo: f64,
+    hi: f64,
+}
+
+impl Clamp {
+    pub fn init(inner: Box<dyn Strategy>, lo: f64, hi: f64) -> Self {
+        Clamp { inner, lo, hi }
+    }
+}
+
+impl Strategy for Clamp {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        self.inner
+            .next_move(last_move, history, ctx)
+            .clamp(self.lo, self.hi)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn on_match_start(&mut self, info: &MatchInfo) {
+        self.inner.on_match_start(info);
+    }
+
+    fn on_match_end(&mut self, result: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        self.inner.on_match_end(result, history, ctx);
+    }
+}
+
+/// Rounds every move of `inner` to the nearest of `levels` evenly spaced values, 2 levels only plays pure moves
+#[derive(Clone)]
+pub struct Quantize {
+    inner: Box<dyn Strategy>,
+    levels: usize,
+}
+
+impl Quantize {
+    pub fn init(inner: Box<dyn Strategy>, levels: usize) -> Self {
+        Quantize {
+            inner,
+            levels: levels.max(2),
+        }
+    }
+}
+
+impl Strategy for Quantize {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        let steps = (self.levels - 1) as f64;
+        let m = self.inner.next_move(last_move, history, ctx);
+        (m.clamp(COOPERATE, DEFECT) * steps).round() / steps
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn on_match_start(&mut self, info: &MatchInfo) {
+        self.inner.on_match_start(info);
+    }
+
+    fn on_match_end(&mut self, result: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        self.inner.on_match_end(result, history, ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::presets;
+    use crate::registry::{self, to_id};
+
+    #[test]
+    fn presets_are_registered_under_their_spec() {
+        for spec in presets() {
+            let registered = registry::with_registry(|registry| {
+                registry
+                    .iter()
+                    .find(|r| r.info.name == spec)
+                    .map(|r| r.info.clone())
+            })
+            .unwrap_or_else(|| panic!("{} is not registered", spec));
+            assert_eq!(registered.id, to_id(spec));
+        }
+    }
+
+    #[test]
+    fn specs_round_trip_through_their_name() {
+        for (spec, name) in [
+            ("noisy(tit-for-tat)", "noisy(tit-for-tat, sigma=0.1)"),
+            (
+                "clamp( tit-for-tat ,hi=0.8,lo=0.2 )",
+                "clamp(tit-for-tat, hi=0.8, lo=0.2)",
+            ),
+            (
+                "mixture-sample(2 * pavlov, grim)",
+                "mixture-sample(2 * pavlov, grim)",
+            ),
+            (
+                "switch(noisy(grim, sigma=0.2), quantize(pavlov), after=10)",
+                "switch(noisy(grim, sigma=0.2), quantize(pavlov, levels=2), after=10)",
+            ),
+        ] {
+            let built = registry::build(spec).unwrap().info;
+            assert_eq!(built.name, name);
+            assert_eq!(built.id, to_id(name));
+
+            let rebuilt = registry::build(&built.name).unwrap().info;
+            assert_eq!((rebuilt.id, rebuilt.name), (built.id, built.name));
+        }
+    }
+
+    #[test]
+    fn bad_specs_are_rejected() {
+        for spec in [
+            "noisy(tit-for-tat, grim)",
+            "noisy(0.5 * tit-for-tat)",
+            "noisy(tit-for-tat, sigma=2)",
+            "noisy(tit-for-tat, width=0.1)",
+            "noisy(tit-for-tat, sigma=0.1, sigma=0.2)",
+            "mixture(tit-for-tat)",
+            "mixture(-1 * tit-for-tat, grim)",
+            "mixture(heavy * tit-for-tat, grim)",
+            "clamp(grim, lo=0.8, hi=0.2)",
+            "quantize(grim, levels=2.5)",
+            "switch(grim, no-such-strategy)",
+            "invert(grim",
+            "no-such-combinator(grim)",
+        ] {
+            assert!(registry::build(spec).is_err(), "{} was accepted", spec);
+        }
+    }
+}
@@ -1,35 +1,155 @@
-use crate::{from_functional, GameHistory, GameMove, Strategy, COOPERATE, DEFECT, P, R, S, T};
+use rand::Rng;
+
+use crate::{
+    from_contextual, from_functional,
+    registry::{Family, Registered, Template},
+    Context, GameHistory, GameMove, MatchInfo, Strategy, COOPERATE, DEFECT,
+};
 
 use super::utils;
 
-pub fn all() -> Vec<(&'static str, Box<dyn Strategy>)> {
+pub fn all() -> Vec<Registered> {
     vec![
-        ("Ambivalent", from_functional(ambivalent)),
-        (
+        Registered::new(
+            "ambivalent",
+            "Ambivalent",
+            Family::Continuous,
+            from_functional(ambivalent),
+        )
+        .description("Always plays 0.5")
+        .memory(0),
+        Registered::new(
+            "ambivalent-suspicious",
             "Ambivalent Suspicious",
+            Family::Continuous,
             from_functional(ambivalent_suspicious),
-        ),
-        ("Ambivalent Relaxed", from_functional(ambivalent_relaxed)),
-        ("Random", from_functional(random)),
-        ("Tit for Tat", from_functional(tit_for_tat)),
-        (
+        )
+        .description("Always plays 0.75")
+        .memory(0),
+        Registered::new(
+            "ambivalent-relaxed",
+            "Ambivalent Relaxed",
+            Family::Continuous,
+            from_functional(ambivalent_relaxed),
+        )
+        .description("Always plays 0.25")
+        .memory(0),
+        Registered::new(
+            "random",
+            "Random",
+            Family::Continuous,
+            from_contextual(random),
+        )
+        .description("Plays a uniformly random move")
+        .stochastic()
+        .memory(0),
+        Registered::new(
+            "tit-for-tat",
+            "Tit for Tat",
+            Family::Continuous,
+            from_functional(tit_for_tat),
+        )
+        .description("Cooperates first, then copies the opponent's last move")
+        .author("Anatol Rapoport")
+        .memory(1),
+        Registered::new(
+            "suspicious-tit-for-tat",
             "Suspicious Tit for Tat",
+            Family::Continuous,
             from_functional(suspicious_tit_for_tat),
-        ),
-        (
+        )
+        .description("Defects first, then copies the opponent's last move")
+        .memory(1),
+        Registered::new(
+            "generous-tit-for-tat",
             "Generous Tit for Tat",
-            from_functional(generous_tit_for_tat),
-        ),
-        (
+            Family::Continuous,
+            from_contextual(generous_tit_for_tat),
+        )
+        .description("Copies the opponent's last move, but forgives it with a chance derived from the payoff when it reads as a defection")
+        .author("Nowak & Sigmund")
+        .stochastic()
+        .memory(1),
+        Registered::new(
+            "imperfect-tit-for-tat",
+            "Imperfect Tit for Tat",
+            Family::Continuous,
+            from_contextual(imperfect_tit_for_tat),
+        )
+        .description("Copies the opponent's last move 95% of the time, and plays its opposite otherwise")
+        .stochastic()
+        .memory(1),
+        Registered::new(
+            "imprecise-tit-for-tat",
             "Imprecise Tit for Tat",
-            from_functional(imprecise_tit_for_tat),
-        ),
-        ("Tit for Two Tats", from_functional(tit_for_two_tats)),
-        ("Two Tits for Tat", from_functional(two_tits_for_tat)),
-        ("Grim", from_functional(grim)),
-        ("2Pavlov", Box::new(NPavlov::init(2.0))),
-        ("4Pavlov", Box::new(NPavlov::init(4.0))),
-        ("8Pavlov", Box::new(NPavlov::init(8.0))),
+            Family::Continuous,
+            from_contextual(imprecise_tit_for_tat),
+        )
+        .description("Copies the opponent's last move read as a pure move, jittered by up to 0.05")
+        .stochastic()
+        .memory(1),
+        Registered::new(
+            "tit-for-two-tats",
+            "Tit for Two Tats",
+            Family::Continuous,
+            from_functional(tit_for_two_tats),
+        )
+        .description("Plays the most cooperative of the opponent's last two moves")
+        .author("John Maynard Smith")
+        .memory(2),
+        Registered::new(
+            "two-tits-for-tat",
+            "Two Tits for Tat",
+            Family::Continuous,
+            from_functional(two_tits_for_tat),
+        )
+        .description("Plays the most defective of the opponent's last two moves")
+        .memory(2),
+        Registered::new("grim", "Grim", Family::Continuous, from_functional(grim))
+            .description("Plays the most defective move the opponent has played so far")
+            .author("James W. Friedman"),
+        Registered::new(
+            "pavlov",
+            "Pavlov",
+            Family::Continuous,
+            from_functional(pavlov),
+        )
+        .description("Win-stay lose-shift, blending the responses to the four outcomes")
+        .author("Kraines & Kraines")
+        .memory(1),
+        npavlov("2pavlov", "2Pavlov", &[2.0]),
+        npavlov("4pavlov", "4Pavlov", &[4.0]),
+        npavlov("8pavlov", "8Pavlov", &[8.0]),
+    ]
+}
+
+pub fn templates() -> Vec<Template> {
+    vec![Template::new("npavlov", npavlov).integer_parameter("n", 4, 1..=1000)]
+}
+
+fn npavlov(id: &str, name: &str, values: &[f64]) -> Registered {
+    let n = values[0];
+    Registered::new(id, name, Family::Continuous, Box::new(NPavlov::init(n)))
+        .description("Plays its chance of defecting, which moves by 1/n towards cooperating after R or P and away after T or S")
+        .author("Kraines & Kraines")
+        .parameter("n", n)
+        .stochastic()
+}
+
+/// Every classic strategy and the continuous strategy that plays like it whenever both players only play pure moves
+/// The unconditional strategies only ever play pure moves, so they are their own counterparts
+/// Random and the NPavlovs are left out: they play a chance of defecting as a move where the classic ones draw a pure
+/// move from it, so both only agree on average
+pub fn counterparts() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("Classic Tit for Tat", "Tit for Tat"),
+        ("Classic Suspicious Tit for Tat", "Suspicious Tit for Tat"),
+        ("Classic Generous Tit for Tat", "Generous Tit for Tat"),
+        ("Classic Imperfect Tit for Tat", "Imperfect Tit for Tat"),
+        ("Classic Tit for Two Tats", "Tit for Two Tats"),
+        ("Classic Two Tits for Tat", "Two Tits for Tat"),
+        ("Classic Grim", "Grim"),
+        ("Classic Pavlov", "Pavlov"),
     ]
 }
 
@@ -45,8 +165,8 @@ pub fn ambivalent_relaxed(_: &GameHistory) -> f64 {
     0.25
 }
 
-pub fn random(_: &GameHistory) -> f64 {
-    rand::random()
+pub fn random(_: &GameHistory, ctx: &mut Context) -> f64 {
+    ctx.rng.gen()
 }
 
 pub fn tit_for_tat(history: &GameHistory) -> f64 {
@@ -57,14 +177,14 @@ pub fn suspicious_tit_for_tat(history: &GameHistory) -> f64 {
     history.last().map_or(DEFECT, utils::to_opponent_move)
 }
 
-pub fn generous_tit_for_tat(history: &GameHistory) -> f64 {
+pub fn generous_tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
     history
         .last()
         .map(utils::to_opponent_move)
         .map_or(DEFECT, |opponent_move| {
-            if utils::is_defection(&opponent_move) {
-                let g: f64 = (1.0 - ((T - R) / (R - S))).min((R - P) / (T - P));
-                if rand::random::<f64>() < g {
+            if utils::is_defection(&opponent_move, &mut ctx.rng) {
+                let g = ctx.payoff.generosity();
+                if ctx.rng.gen::<f64>() < g {
                     COOPERATE
                 } else {
                     opponent_move
@@ -75,21 +195,41 @@ pub fn generous_tit_for_tat(history: &GameHistory) -> f64 {
         })
 }
 
-pub fn imprecise_tit_for_tat(history: &GameHistory) -> f64 {
+/// mirrors the opponent's move, but plays its opposite 5% of the time
+pub fn imperfect_tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
+    const ACCURACY: f64 = 0.95;
+
+    history
+        .last()
+        .map(utils::to_opponent_move)
+        .map_or(COOPERATE, |opponent_move| {
+            if ctx.rng.gen::<f64>() < ACCURACY {
+                opponent_move
+            } else {
+                utils::to_opposite(opponent_move)
+            }
+        })
+}
+
+pub fn imprecise_tit_for_tat(history: &GameHistory, ctx: &mut Context) -> f64 {
     const DELTA: f64 = 0.05;
 
     history
         .last()
         .map(utils::to_opponent_move)
         .map_or(COOPERATE, |opponent_move| {
-            let opponent_move = utils::to_nearest_move(opponent_move);
+            let opponent_move = utils::to_nearest_move(opponent_move, &mut ctx.rng);
 
-            opponent_move + ((rand::random::<f64>() * DELTA * 2.0) - DELTA)
+            opponent_move + ((ctx.rng.gen::<f64>() * DELTA * 2.0) - DELTA)
         })
 }
 
 /// responds with the most cooperative of the last two opponent's moves
 pub fn tit_for_two_tats(history: &GameHistory) -> f64 {
+    if history.len() < 2 {
+        return COOPERATE;
+    }
+
     history
         .iter()
         .rev()
@@ -119,6 +259,14 @@ pub fn grim(history: &GameHistory) -> f64 {
         .unwrap_or(COOPERATE)
 }
 
+/// win-stay lose-shift, blending the four outcomes: keeps its move after R and T, switches after P and S
+pub fn pavlov(history: &GameHistory) -> f64 {
+    history.last().map_or(COOPERATE, |GameMove(m, o)| {
+        let (m, o) = (m.clamp(COOPERATE, DEFECT), o.clamp(COOPERATE, DEFECT));
+        m * (1.0 - o) + (1.0 - m) * o
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct NPavlov {
     n: f64,
@@ -133,9 +281,17 @@ impl NPavlov {
 
 impl Strategy for NPavlov {
     // unsure if https://plato.stanford.edu/entries/prisoner-dilemma/strategy-table.html has the right implementation?
-    fn next_move(&mut self, last_move: Option<GameMove>, _history: &GameHistory) -> f64 {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        _history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
         self.p += last_move.map_or(COOPERATE, |GameMove(m, o)| {
-            match (utils::is_defection(&m), utils::is_defection(&o)) {
+            match (
+                utils::is_defection(&m, &mut ctx.rng),
+                utils::is_defection(&o, &mut ctx.rng),
+            ) {
                 (false, false) | (true, true) => 1.0 / self.n,  // R, P
                 (false, true) | (true, false) => -1.0 / self.n, // T, S
             }
@@ -144,4 +300,30 @@ impl Strategy for NPavlov {
 
         1.0 - self.p
     }
+
+    fn reset(&mut self) {
+        self.p = 1.0;
+    }
+
+    /// p follows the current opponent, so every match starts from cooperating
+    fn on_match_start(&mut self, _: &MatchInfo) {
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(theirs: &[f64]) -> GameHistory {
+        theirs.iter().map(|&m| GameMove(COOPERATE, m)).collect()
+    }
+
+    #[test]
+    fn tit_for_two_tats_waits_for_two_moves() {
+        assert_eq!(tit_for_two_tats(&history(&[])), COOPERATE);
+        assert_eq!(tit_for_two_tats(&history(&[DEFECT])), COOPERATE);
+        assert_eq!(tit_for_two_tats(&history(&[DEFECT, DEFECT])), DEFECT);
+        assert_eq!(tit_for_two_tats(&history(&[0.25, 0.75])), 0.25);
+    }
 }
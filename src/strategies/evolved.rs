@@ -0,0 +1,249 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    registry::{to_id, ErrorLog, Family, Registered},
+    Context, GameHistory, GameMove, Strategy, COOPERATE, DEFECT,
+};
+
+/// Directory searched for exported strategies, relative to the working directory
+pub const EVOLVED_DIR: &str = "strategies";
+pub const EVOLVED_EXTENSION: &str = "memn";
+
+static ERRORS: ErrorLog = ErrorLog::new();
+
+/// Strategies written by `evolve`, loaded from `EVOLVED_DIR` so they can be entered like any other
+/// Files that can't be read are left out and their error kept for `errors`, the others come with their file
+pub fn all() -> Vec<(PathBuf, Registered)> {
+    load_dir(Path::new(EVOLVED_DIR))
+        .into_iter()
+        .filter_map(|result| match result {
+            Ok((path, name, strategy)) => Some((
+                path,
+                Registered::new(
+                    &format!("evolved-{}", to_id(&name)),
+                    &name,
+                    Family::Custom,
+                    Box::new(strategy.clone()),
+                )
+                .description("Memory-n lookup table evolved by the genetic algorithm")
+                .parameter("depth", strategy.depth as f64)
+                .memory(strategy.depth),
+            )),
+            Err((path, e)) => {
+                ERRORS.record(&path.display().to_string(), &e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// First error of every file in `EVOLVED_DIR` that could not be read since the last `clear_errors`
+pub fn errors() -> Vec<(String, String)> {
+    ERRORS.errors()
+}
+
+pub fn clear_errors() {
+    ERRORS.clear();
+}
+
+/// A continuous memory-n strategy: a lookup table of responses to every pure outcome of the last `depth` rounds,
+/// blended multilinearly for continuous moves
+/// Table index bits, from the lowest: my move one round ago, their move one round ago, my move two rounds ago, ...
+/// so for memory-one the entries answer CC, DC, CD and DD (mine first)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryN {
+    pub depth: usize,
+    /// Moves played before there are `depth` rounds to look back on
+    pub opening: Vec<f64>,
+    pub table: Vec<f64>,
+}
+
+impl MemoryN {
+    pub fn new(depth: usize, opening: Vec<f64>, table: Vec<f64>) -> Result<Self, String> {
+        let strategy = MemoryN {
+            depth,
+            opening,
+            table,
+        };
+        strategy.validate()?;
+        Ok(strategy)
+    }
+
+    /// Number of table entries for a given depth
+    pub fn table_len(depth: usize) -> usize {
+        1 << (2 * depth)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.depth == 0 {
+            return Err(String::from("depth must be at least 1"));
+        }
+        if self.opening.len() != self.depth {
+            return Err(format!(
+                "a memory-{} strategy needs {} opening moves, got {}",
+                self.depth,
+                self.depth,
+                self.opening.len()
+            ));
+        }
+        if self.table.len() != Self::table_len(self.depth) {
+            return Err(format!(
+                "a memory-{} strategy needs {} table entries, got {}",
+                self.depth,
+                Self::table_len(self.depth),
+                self.table.len()
+            ));
+        }
+        if self
+            .opening
+            .iter()
+            .chain(&self.table)
+            .any(|m| !(COOPERATE..=DEFECT).contains(m))
+        {
+            return Err(String::from("moves must be between 0 and 1"));
+        }
+        Ok(())
+    }
+
+    /// Every gene of the strategy, opening first
+    pub fn genes(&self) -> impl Iterator<Item = &f64> {
+        self.opening.iter().chain(&self.table)
+    }
+
+    pub fn genes_mut(&mut self) -> impl Iterator<Item = &mut f64> {
+        self.opening.iter_mut().chain(&mut self.table)
+    }
+
+    /// The response to the last `depth` moves, most recent first
+    pub fn respond(&self, recent: &[GameMove]) -> f64 {
+        let inputs = recent
+            .iter()
+            .flat_map(|GameMove(mine, theirs)| [*mine, *theirs])
+            .map(|m| m.clamp(COOPERATE, DEFECT));
+
+        // collapse one input at a time, halving the table every step
+        let mut values = self.table.clone();
+        for x in inputs {
+            values = values
+                .chunks_exact(2)
+                .map(|pair| (1.0 - x) * pair[0] + x * pair[1])
+                .collect();
+        }
+        values[0]
+    }
+
+    /// Text form read back by `parse`, one `key = value` per line
+    pub fn to_spec(&self, name: &str) -> String {
+        let list = |values: &[f64]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        format!(
+            "name = {}\ndepth = {}\nopening = {}\ntable = {}\n",
+            name,
+            self.depth,
+            list(&self.opening),
+            list(&self.table)
+        )
+    }
+
+    /// Reads the format written by `to_spec`, returning the name and the strategy
+    pub fn parse(spec: &str) -> Result<(String, MemoryN), String> {
+        let mut name = None;
+        let mut depth = None;
+        let mut opening = None;
+        let mut table = None;
+
+        for (number, line) in spec.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected 'key = value'", number + 1))?;
+            let value = value.trim();
+
+            let list = || {
+                value
+                    .split(',')
+                    .map(|v| {
+                        v.trim().parse::<f64>().map_err(|_| {
+                            format!("line {}: '{}' is not a number", number + 1, v.trim())
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            };
+
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "depth" => {
+                    depth =
+                        Some(value.parse::<usize>().map_err(|_| {
+                            format!("line {}: '{}' is not a depth", number + 1, value)
+                        })?)
+                }
+                "opening" => opening = Some(list()?),
+                "table" => table = Some(list()?),
+                key => return Err(format!("line {}: unknown key '{}'", number + 1, key)),
+            }
+        }
+
+        let name = name.ok_or("missing 'name'")?;
+        let strategy = MemoryN::new(
+            depth.ok_or("missing 'depth'")?,
+            opening.ok_or("missing 'opening'")?,
+            table.ok_or("missing 'table'")?,
+        )?;
+        Ok((name, strategy))
+    }
+}
+
+impl Strategy for MemoryN {
+    fn next_move(&mut self, _: Option<GameMove>, history: &GameHistory, _: &mut Context) -> f64 {
+        match self.opening.get(history.len()) {
+            Some(&opening) => opening,
+            None => {
+                let recent: Vec<_> = history.iter().rev().take(self.depth).cloned().collect();
+                self.respond(&recent)
+            }
+        }
+    }
+}
+
+/// A strategy read from a file, with its path and name, or the path and the reason it could not be read
+pub type Loaded = Result<(PathBuf, String, MemoryN), (PathBuf, String)>;
+
+/// Every `EVOLVED_EXTENSION` file in `dir`, sorted by file name, with the path of those that could not be read
+pub fn load_dir(dir: &Path) -> Vec<Loaded> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == EVOLVED_EXTENSION))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|spec| MemoryN::parse(&spec))
+                .map(|(name, strategy)| (path.clone(), name, strategy))
+                .map_err(|e| (path, e))
+        })
+        .collect()
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+use crate::{
+    registry::{Family, Registered, Template},
+    Context, GameHistory, GameMove, GameResult, Strategy, COOPERATE, DEFECT,
+};
+
+/// Strategies that learn from the points they get during a match
+/// They start from scratch in every match, unless the tournament carries over what they learned between repetitions
+pub fn all() -> Vec<Registered> {
+    vec![
+        q_learner("q-learner", "Q-Learner", &[2.0, 1.0, 0.1, 0.9, 0.1]),
+        q_learner(
+            "q-learner-fine",
+            "Q-Learner Fine",
+            &[5.0, 1.0, 0.1, 0.9, 0.1],
+        ),
+        q_learner(
+            "q-learner-memory-two",
+            "Q-Learner Memory Two",
+            &[2.0, 2.0, 0.1, 0.9, 0.1],
+        ),
+        reinforce("reinforce", "REINFORCE", &[1.0, 0.05, 0.2]),
+    ]
+}
+
+pub fn templates() -> Vec<Template> {
+    vec![
+        Template::new("q-learner", q_learner)
+            .integer_parameter("bins", 2, 2..=8)
+            .integer_parameter("memory", 1, 0..=3)
+            .parameter("learning_rate", 0.1, 0.0..=1.0)
+            .parameter("discount", 0.9, 0.0..=1.0)
+            .parameter("exploration", 0.1, 0.0..=1.0),
+        Template::new("reinforce", reinforce)
+            .integer_parameter("memory", 1, 0..=10)
+            .parameter("learning_rate", 0.05, 0.0..=1.0)
+            .parameter("exploration", 0.2, 0.0..=1.0),
+    ]
+}
+
+fn q_learner(id: &str, name: &str, values: &[f64]) -> Registered {
+    let &[bins, memory, learning_rate, discount, exploration] = values else {
+        unreachable!("q-learner has five parameters");
+    };
+    Registered::new(
+        id,
+        name,
+        Family::Continuous,
+        Box::new(QLearner::init(
+            bins as usize,
+            memory as usize,
+            learning_rate,
+            discount,
+            exploration,
+        )),
+    )
+    .description("Tabular Q-learning over the last rounds and its own moves, discretised into evenly spaced values")
+    .parameter("bins", bins)
+    .parameter("memory", memory)
+    .parameter("learning_rate", learning_rate)
+    .parameter("discount", discount)
+    .parameter("exploration", exploration)
+    .stochastic()
+}
+
+fn reinforce(id: &str, name: &str, values: &[f64]) -> Registered {
+    let &[memory, learning_rate, exploration] = values else {
+        unreachable!("reinforce has three parameters");
+    };
+    Registered::new(
+        id,
+        name,
+        Family::Continuous,
+        Box::new(Reinforce::init(memory as usize, learning_rate, exploration)),
+    )
+    .description("Policy gradient with a gaussian policy whose mean follows the last rounds")
+    .author("Ronald J. Williams")
+    .parameter("memory", memory)
+    .parameter("learning_rate", learning_rate)
+    .parameter("exploration", exploration)
+    .stochastic()
+}
+
+/// Tabular Q-learning, moves and the last `memory` rounds are discretised into `bins` evenly spaced values
+/// Picks a uniformly random move with chance `exploration`, otherwise the move with the highest value for the current state
+#[derive(Debug, Clone)]
+pub struct QLearner {
+    bins: usize,
+    memory: usize,
+    learning_rate: f64,
+    /// Weight of the value of the next state
+    discount: f64,
+    exploration: f64,
+    /// Value of every move in every state visited so far, one row of `bins` values per state
+    /// Rows are added as states are reached, as the full table grows as `(bins + 1)^(2 * memory)` and most of it is never seen
+    values: HashMap<usize, Vec<f64>>,
+    /// State and move of the previous round, updated once its points are known
+    last: Option<(usize, usize)>,
+}
+
+impl QLearner {
+    pub fn init(
+        bins: usize,
+        memory: usize,
+        learning_rate: f64,
+        discount: f64,
+        exploration: f64,
+    ) -> Self {
+        QLearner {
+            bins: bins.max(2),
+            memory,
+            learning_rate,
+            discount,
+            exploration,
+            values: HashMap::new(),
+            last: None,
+        }
+    }
+
+    /// Every remembered move is a bin or missing, before that many rounds were played
+    fn state(&self, history: &GameHistory) -> usize {
+        let symbols = self.bins + 1;
+        let mut recent = history.iter().rev();
+
+        (0..self.memory).fold(0, |state, _| match recent.next() {
+            Some(GameMove(mine, theirs)) => {
+                (state * symbols + self.bin(*mine) + 1) * symbols + self.bin(*theirs) + 1
+            }
+            None => state * symbols * symbols,
+        })
+    }
+
+    fn bin(&self, m: f64) -> usize {
+        (m.clamp(COOPERATE, DEFECT) * (self.bins - 1) as f64).round() as usize
+    }
+
+    /// Value of `action` in `state`, zero in states that were never visited
+    fn value(&self, state: usize, action: usize) -> f64 {
+        self.values.get(&state).map_or(0.0, |row| row[action])
+    }
+
+    fn best_value(&self, state: usize) -> f64 {
+        (0..self.bins)
+            .map(|action| self.value(state, action))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Moves the value of the previous round's state and move towards `target`
+    fn learn(&mut self, target: f64) {
+        if let Some((state, action)) = self.last.take() {
+            let bins = self.bins;
+            let value = &mut self.values.entry(state).or_insert_with(|| vec![0.0; bins])[action];
+            *value += self.learning_rate * (target - *value);
+        }
+    }
+
+    /// Best move of `state`, ties are broken uniformly so that an untrained learner does not always cooperate
+    fn greedy(&self, state: usize, ctx: &mut Context) -> usize {
+        let best = self.best_value(state);
+        let ties: Vec<_> = (0..self.bins)
+            .filter(|&action| self.value(state, action) == best)
+            .collect();
+        ties[ctx.rng.gen_range(0..ties.len())]
+    }
+}
+
+impl Strategy for QLearner {
+    fn next_move(&mut self, _: Option<GameMove>, history: &GameHistory, ctx: &mut Context) -> f64 {
+        let state = self.state(history);
+
+        if let Some(last_move) = history.last() {
+            let future = self.best_value(state);
+            self.learn(ctx.points(last_move) + self.discount * future);
+        }
+
+        let action = if ctx.rng.gen::<f64>() < self.exploration {
+            ctx.rng.gen_range(0..self.bins)
+        } else {
+            self.greedy(state, ctx)
+        };
+        self.last = Some((state, action));
+
+        action as f64 / (self.bins - 1) as f64
+    }
+
+    fn reset(&mut self) {
+        self.values.clear();
+        self.last = None;
+    }
+
+    /// The last round has no next state to look ahead to
+    fn on_match_end(&mut self, _: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        match history.last() {
+            Some(last_move) => self.learn(ctx.points(last_move)),
+            None => self.last = None,
+        }
+    }
+}
+
+/// Policy gradient with a gaussian policy, whose mean is the logistic of a weighted sum of the last `memory` rounds
+/// Learns after every round from the points of the previous move, measured against the average points so far
+#[derive(Debug, Clone)]
+pub struct Reinforce {
+    memory: usize,
+    learning_rate: f64,
+    /// Standard deviation of the policy
+    exploration: f64,
+    /// A bias followed by one weight per remembered move
+    weights: Vec<f64>,
+    baseline: f64,
+    rewards: usize,
+    /// Features, mean and sampled move of the previous round
+    last: Option<(Vec<f64>, f64, f64)>,
+}
+
+impl Reinforce {
+    pub fn init(memory: usize, learning_rate: f64, exploration: f64) -> Self {
+        Reinforce {
+            memory,
+            learning_rate,
+            exploration: exploration.max(1e-3),
+            weights: vec![0.0; 1 + 2 * memory],
+            baseline: 0.0,
+            rewards: 0,
+            last: None,
+        }
+    }
+
+    /// Remembered moves centered on 0, rounds that were not played yet count as 0
+    fn features(&self, history: &GameHistory) -> Vec<f64> {
+        let mut recent = history.iter().rev();
+
+        let moves = (0..self.memory).flat_map(|_| match recent.next() {
+            Some(GameMove(mine, theirs)) => [mine - 0.5, theirs - 0.5],
+            None => [0.0, 0.0],
+        });
+        [1.0].into_iter().chain(moves).collect()
+    }
+
+    fn mean(&self, features: &[f64]) -> f64 {
+        let z: f64 = self.weights.iter().zip(features).map(|(w, x)| w * x).sum();
+        1.0 / (1.0 + (-z).exp())
+    }
+
+    /// Rewards the previous round's sampled move with `reward`
+    fn learn(&mut self, reward: f64) {
+        let Some((features, mean, sampled)) = self.last.take() else {
+            return;
+        };
+        self.rewards += 1;
+        self.baseline += (reward - self.baseline) / self.rewards as f64;
+
+        // gradient of the log likelihood of the sampled move, through the logistic mean
+        let variance = self.exploration * self.exploration;
+        let scale = self.learning_rate * (reward - self.baseline) * (sampled - mean) / variance
+            * mean
+            * (1.0 - mean);
+        for (w, x) in self.weights.iter_mut().zip(&features) {
+            *w += scale * x;
+        }
+    }
+}
+
+impl Strategy for Reinforce {
+    fn next_move(&mut self, _: Option<GameMove>, history: &GameHistory, ctx: &mut Context) -> f64 {
+        if let Some(last_move) = history.last() {
+            self.learn(ctx.points(last_move));
+        }
+
+        let features = self.features(history);
+        let mean = self.mean(&features);
+        let sampled = match Normal::new(mean, self.exploration) {
+            Ok(policy) => policy.sample(&mut ctx.rng),
+            Err(_) => mean,
+        };
+
+        self.last = Some((features, mean, sampled));
+        sampled.clamp(COOPERATE, DEFECT)
+    }
+
+    fn reset(&mut self) {
+        self.weights.fill(0.0);
+        self.baseline = 0.0;
+        self.rewards = 0;
+        self.last = None;
+    }
+
+    fn on_match_end(&mut self, _: &GameResult, history: &GameHistory, ctx: &mut Context) {
+        match history.last() {
+            Some(last_move) => self.learn(ctx.points(last_move)),
+            None => self.last = None,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    payoff::Payoff,
+    registry::{Family, Registered, Template},
+    Context, GameHistory, GameMove, Strategy, COOPERATE, DEFECT,
+};
+
+use super::evolved::MemoryN;
+
+pub fn all() -> Vec<Registered> {
+    vec![
+        memory_one(
+            "memory-one-tit-for-tat",
+            "Memory-One Tit for Tat",
+            &MemoryOne::tit_for_tat().values(),
+        ),
+        // a fixed table cannot follow the tournament's payoff, so this one is tuned for the canonical payoff
+        memory_one(
+            "memory-one-generous-tit-for-tat",
+            "Memory-One Generous Tit for Tat",
+            &MemoryOne::generous_tit_for_tat(Payoff::CANONICAL.generosity()).values(),
+        )
+        .description(
+            "Tit for Tat forgiving a defection with Nowak & Sigmund's generosity for the canonical 3/1/5/0 payoff (1/3), whatever payoff is played",
+        ),
+        memory_one(
+            "memory-one-pavlov",
+            "Memory-One Pavlov",
+            &MemoryOne::pavlov().values(),
+        ),
+        memory_one(
+            "memory-one-cooperator",
+            "Memory-One Cooperator",
+            &MemoryOne::cooperator().values(),
+        ),
+        memory_one(
+            "memory-one-defector",
+            "Memory-One Defector",
+            &MemoryOne::defector().values(),
+        ),
+    ]
+}
+
+pub fn templates() -> Vec<Template> {
+    vec![Template::new("memory-one", memory_one)
+        .parameter("opening", COOPERATE, COOPERATE..=DEFECT)
+        .parameter("cc", COOPERATE, COOPERATE..=DEFECT)
+        .parameter("dc", COOPERATE, COOPERATE..=DEFECT)
+        .parameter("cd", DEFECT, COOPERATE..=DEFECT)
+        .parameter("dd", DEFECT, COOPERATE..=DEFECT)]
+}
+
+fn memory_one(id: &str, name: &str, values: &[f64]) -> Registered {
+    let &[opening, cc, dc, cd, dd] = values else {
+        unreachable!("memory-one has five parameters");
+    };
+    Registered::new(
+        id,
+        name,
+        Family::Continuous,
+        Box::new(MemoryOne::new(opening, cc, dc, cd, dd)),
+    )
+    .description(
+        "Answers the previous round with one move per outcome, blending them for continuous moves",
+    )
+    .parameter("opening", opening)
+    .parameter("cc", cc)
+    .parameter("dc", dc)
+    .parameter("cd", cd)
+    .parameter("dd", dd)
+    .memory(1)
+}
+
+/// Responds to the previous round only: one move for each of the four pure outcomes, plus the opening move
+/// Outcomes are named by my move then theirs, so `cd` is the response after I cooperated and they defected,
+/// and are kept in the order of a depth one `MemoryN` table: CC, DC, CD, DD
+/// Continuous moves blend the four responses bilinearly, treating each move as a chance of defecting
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryOne {
+    pub opening: f64,
+    pub cc: f64,
+    pub dc: f64,
+    pub cd: f64,
+    pub dd: f64,
+}
+
+impl MemoryOne {
+    pub fn new(opening: f64, cc: f64, dc: f64, cd: f64, dd: f64) -> Self {
+        MemoryOne {
+            opening,
+            cc,
+            dc,
+            cd,
+            dd,
+        }
+    }
+
+    /// Tit for Tat: cooperate first, then copy the opponent
+    pub fn tit_for_tat() -> Self {
+        MemoryOne::new(COOPERATE, COOPERATE, COOPERATE, DEFECT, DEFECT)
+    }
+
+    /// Tit for Tat that forgives a defection with chance `generosity`
+    pub fn generous_tit_for_tat(generosity: f64) -> Self {
+        let punish = DEFECT - generosity;
+        MemoryOne::new(COOPERATE, COOPERATE, COOPERATE, punish, punish)
+    }
+
+    /// Win-stay lose-shift: keeps its move after R or T, switches after P or S
+    pub fn pavlov() -> Self {
+        MemoryOne::new(COOPERATE, COOPERATE, DEFECT, DEFECT, COOPERATE)
+    }
+
+    pub fn cooperator() -> Self {
+        MemoryOne::new(COOPERATE, COOPERATE, COOPERATE, COOPERATE, COOPERATE)
+    }
+
+    pub fn defector() -> Self {
+        MemoryOne::new(DEFECT, DEFECT, DEFECT, DEFECT, DEFECT)
+    }
+
+    /// The opening followed by the responses, in the order of `new`
+    pub fn values(&self) -> [f64; 5] {
+        [self.opening, self.cc, self.dc, self.cd, self.dd]
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .values()
+            .iter()
+            .any(|m| !(COOPERATE..=DEFECT).contains(m))
+        {
+            return Err(String::from("moves must be between 0 and 1"));
+        }
+        Ok(())
+    }
+
+    pub fn respond(&self, GameMove(mine, theirs): &GameMove) -> f64 {
+        let mine = mine.clamp(COOPERATE, DEFECT);
+        let theirs = theirs.clamp(COOPERATE, DEFECT);
+
+        (1.0 - mine) * (1.0 - theirs) * self.cc
+            + mine * (1.0 - theirs) * self.dc
+            + (1.0 - mine) * theirs * self.cd
+            + mine * theirs * self.dd
+    }
+}
+
+impl From<MemoryOne> for MemoryN {
+    fn from(strategy: MemoryOne) -> Self {
+        MemoryN {
+            depth: 1,
+            opening: vec![strategy.opening],
+            table: vec![strategy.cc, strategy.dc, strategy.cd, strategy.dd],
+        }
+    }
+}
+
+impl Strategy for MemoryOne {
+    fn next_move(&mut self, last_move: Option<GameMove>, _: &GameHistory, _: &mut Context) -> f64 {
+        last_move.map_or(self.opening, |m| self.respond(&m))
+    }
+}
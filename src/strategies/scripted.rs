@@ -0,0 +1,269 @@
+// Strategies written as Rhai scripts (https://rhai.rs), loaded from the strategies directory without recompiling
+//
+// A script defines `fn next_move(mine, theirs, info)` and returns a move between 0 (cooperate) and 1 (defect)
+// `mine` and `theirs` are arrays of every move so far as this player saw them, and `info` is a map with
+// `round`, `rounds` (`()` when the length is hidden), `random` (a fresh uniform number every round, drawn from the
+// player's `Context` so matches can be replayed) and the payoff as `reward`, `punishment`, `temptation` and `sucker`
+// State can be kept in `this`, a map that starts empty in every match unless `--carry-over` keeps it from one repetition
+// of a pairing to the next
+// Optional top level constants `NAME`, `DESCRIPTION`, `AUTHOR` and `STOCHASTIC` fill in the registry entry
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    time::SystemTime,
+};
+
+use rand::Rng;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+
+use crate::{
+    registry::{to_id, ErrorLog, Family, Registered},
+    Context, GameHistory, GameMove, MatchInfo, Strategy, COOPERATE, DEFECT,
+};
+
+use super::evolved::EVOLVED_DIR;
+
+/// Scripts are searched next to the evolved strategies
+pub const SCRIPT_DIR: &str = EVOLVED_DIR;
+pub const SCRIPT_EXTENSION: &str = "rhai";
+/// Operations a single call of `next_move` may take, so that a script stuck in a loop fails instead of hanging the tournament
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// First error of every script, by file for scripts that did not load and by strategy id for scripts that failed to play
+static ERRORS: ErrorLog = ErrorLog::new();
+
+/// Every script in `SCRIPT_DIR` that compiles with its file, scripts that don't are left out and their error kept for `errors`
+pub fn all() -> Vec<(PathBuf, Registered)> {
+    load_dir(Path::new(SCRIPT_DIR))
+        .into_iter()
+        .filter_map(|result| match result {
+            Ok(script) => Some((script.path.clone(), script.register())),
+            Err((path, e)) => {
+                ERRORS.record(&path.display().to_string(), &e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// A compiled script and what it says about itself
+#[derive(Clone)]
+pub struct Script {
+    pub path: PathBuf,
+    /// `script-` followed by the file name, so the id stays the same when `NAME` changes
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub stochastic: bool,
+    ast: Arc<AST>,
+}
+
+/// Plays a `Script`, cooperating for the rest of the match once the script fails
+#[derive(Clone)]
+pub struct Scripted {
+    /// Id of the script, its errors are kept under it
+    id: String,
+    ast: Arc<AST>,
+    /// `this` of the script, kept from round to round
+    state: Dynamic,
+    failed: bool,
+}
+
+impl Script {
+    /// Compiles the script at `path` and reads its constants, named by the file when it has no `NAME`
+    pub fn load(path: &Path) -> Result<Script, String> {
+        let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let ast = engine().compile(&source).map_err(|e| e.to_string())?;
+
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == "next_move" && f.params.len() == 3)
+        {
+            return Err(String::from("missing fn next_move(mine, theirs, info)"));
+        }
+
+        let mut scope = Scope::new();
+        engine()
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| e.to_string())?;
+        let constant = |name: &str| scope.get_value::<String>(name).unwrap_or_default();
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Ok(Script {
+            path: path.to_path_buf(),
+            id: format!("script-{}", to_id(&stem)),
+            name: Some(constant("NAME"))
+                .filter(|name| !name.is_empty())
+                .unwrap_or(stem),
+            description: constant("DESCRIPTION"),
+            author: constant("AUTHOR"),
+            stochastic: scope.get_value::<bool>("STOCHASTIC").unwrap_or(false),
+            ast: Arc::new(ast),
+        })
+    }
+
+    pub fn register(&self) -> Registered {
+        let description = if self.description.is_empty() {
+            format!("Script loaded from {}", self.path.display())
+        } else {
+            self.description.clone()
+        };
+
+        let registered = Registered::new(
+            &self.id,
+            &self.name,
+            Family::Custom,
+            Box::new(Scripted {
+                id: self.id.clone(),
+                ast: Arc::clone(&self.ast),
+                state: Dynamic::from_map(Map::new()),
+                failed: false,
+            }),
+        )
+        .description(&description)
+        .author(&self.author);
+
+        if self.stochastic {
+            registered.stochastic()
+        } else {
+            registered
+        }
+    }
+}
+
+impl Strategy for Scripted {
+    fn next_move(&mut self, _: Option<GameMove>, history: &GameHistory, ctx: &mut Context) -> f64 {
+        if self.failed {
+            return COOPERATE;
+        }
+
+        let mine: Vec<Dynamic> = history.iter().map(|m| Dynamic::from_float(m.0)).collect();
+        let theirs: Vec<Dynamic> = history.iter().map(|m| Dynamic::from_float(m.1)).collect();
+
+        let mut info = Map::new();
+        info.insert("round".into(), Dynamic::from_int(history.len() as i64));
+        info.insert(
+            "rounds".into(),
+            ctx.rounds
+                .map_or(Dynamic::UNIT, |rounds| Dynamic::from_int(rounds as i64)),
+        );
+        info.insert("random".into(), Dynamic::from_float(ctx.rng.gen::<f64>()));
+        info.insert("reward".into(), Dynamic::from_float(ctx.payoff.reward));
+        info.insert(
+            "punishment".into(),
+            Dynamic::from_float(ctx.payoff.punishment),
+        );
+        info.insert(
+            "temptation".into(),
+            Dynamic::from_float(ctx.payoff.temptation),
+        );
+        info.insert("sucker".into(), Dynamic::from_float(ctx.payoff.sucker));
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let result = engine().call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &self.ast,
+            "next_move",
+            (mine, theirs, info),
+        );
+
+        let m = match result {
+            Ok(m) => m
+                .as_float()
+                .or_else(|_| m.as_int().map(|m| m as f64))
+                .map_err(|type_name| {
+                    format!("next_move returned {}, expected a number", type_name)
+                }),
+            Err(e) => Err(e.to_string()),
+        };
+        match m {
+            Ok(m) if m.is_finite() => m.clamp(COOPERATE, DEFECT),
+            Ok(m) => {
+                self.fail(&format!("next_move returned {}", m));
+                COOPERATE
+            }
+            Err(e) => {
+                self.fail(&e);
+                COOPERATE
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = Dynamic::from_map(Map::new());
+        self.failed = false;
+    }
+
+    /// A failure only lasts for the rest of its match, `this` is kept when the tournament carries over
+    fn on_match_start(&mut self, _: &MatchInfo) {
+        self.failed = false;
+    }
+}
+
+impl Scripted {
+    fn fail(&mut self, error: &str) {
+        ERRORS.record(&self.id, error);
+        self.failed = true;
+    }
+}
+
+/// One engine is shared by every script, with the same limits for all of them
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+
+    ENGINE.get_or_init(|| {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine
+    })
+}
+
+/// Every script error since the last `clear_errors`, as the file or strategy id and its first error
+pub fn errors() -> Vec<(String, String)> {
+    ERRORS.errors()
+}
+
+pub fn clear_errors() {
+    ERRORS.clear();
+}
+
+fn script_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == SCRIPT_EXTENSION))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Every `SCRIPT_EXTENSION` file in `dir`, sorted by file name, with the path of those that could not be loaded
+pub fn load_dir(dir: &Path) -> Vec<Result<Script, (PathBuf, String)>> {
+    script_paths(dir)
+        .into_iter()
+        .map(|path| Script::load(&path).map_err(|e| (path, e)))
+        .collect()
+}
+
+/// Every script in `dir` with the time it was last modified, changes whenever a script is added, removed or saved
+pub fn fingerprint(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    script_paths(dir)
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
@@ -1,14 +1,32 @@
-use crate::{GameHistory, GameMove, Strategy, DEFECT};
+use crate::{
+    registry::{Family, Registered, Template},
+    Context, GameHistory, GameMove, MatchInfo, Strategy, DEFECT,
+};
 
 // These are TSVRN9's custom strategies
-pub fn all() -> Vec<(&'static str, Box<dyn Strategy>)> {
+pub fn all() -> Vec<Registered> {
     vec![
-        ("Detente", Box::new(Detente::init(1.0, 0.1))),
-        ("Suspicious Detente", Box::new(Detente::init(0.0, 0.1))),
-        ("Trusting Detente", Box::new(Detente::init(1.0, 0.5)))
+        detente("detente", "Detente", &[1.0, 0.1]),
+        detente("suspicious-detente", "Suspicious Detente", &[0.0, 0.1]),
+        detente("trusting-detente", "Trusting Detente", &[1.0, 0.5]),
     ]
 }
 
+pub fn templates() -> Vec<Template> {
+    vec![Template::new("detente", detente)
+        .parameter("comfort", 1.0, 0.0..=1.0)
+        .parameter("trust", 0.1, 0.0..=1.0)]
+}
+
+fn detente(id: &str, name: &str, values: &[f64]) -> Registered {
+    let (comfort, trust) = (values[0], values[1]);
+    Registered::new(id, name, Family::Custom, Box::new(Detente::init(comfort, trust)))
+        .description("Eases towards cooperation as its comfort with the opponent grows, and answers defections in kind")
+        .author("TSVRN9")
+        .parameter("comfort", comfort)
+        .parameter("trust", trust)
+}
+
 /// This strategy keeps track of its "comfort" with other strategies
 /// Comfort is how much each move decrements by. (Smaller numbers represent more cooperation, so as comfort increases, the f64 returns decreases)
 /// Trust is how much comfort increments by, which is constant
@@ -19,6 +37,7 @@ pub fn all() -> Vec<(&'static str, Box<dyn Strategy>)> {
 struct Detente {
     comfort: f64,
     trust: f64,
+    initial_comfort: f64,
 }
 
 impl Detente {
@@ -26,27 +45,35 @@ impl Detente {
         Detente {
             comfort: intial_comfort,
             trust,
+            initial_comfort: intial_comfort,
         }
     }
 }
 
 impl Strategy for Detente {
-    fn next_move(&mut self, last_move: Option<GameMove>, _: &GameHistory) -> f64 {
+    fn next_move(&mut self, last_move: Option<GameMove>, _: &GameHistory, _: &mut Context) -> f64 {
         if let Some(GameMove(_, previous)) = last_move {
             let is_cooperative = previous < (1.0 - (self.comfort / 2.0)).max(0.1);
 
-            let next_move = if is_cooperative {
+            if is_cooperative {
                 let v = previous - self.comfort;
                 self.comfort += self.trust;
                 v
             } else {
                 self.comfort = 0.0;
                 DEFECT
-            };
-
-            next_move
+            }
         } else {
             1.0 - self.comfort
         }
     }
+
+    fn reset(&mut self) {
+        self.comfort = self.initial_comfort;
+    }
+
+    /// Comfort is built up with the current opponent, so every match starts from the initial comfort
+    fn on_match_start(&mut self, _: &MatchInfo) {
+        self.reset();
+    }
 }
@@ -1,6 +1,6 @@
-use rand::random;
+use rand::Rng;
 
-use crate::{GameMove, COOPERATE, DEFECT};
+use crate::{Context, GameHistory, GameMove, COOPERATE, DEFECT};
 
 pub fn to_opponent_move(GameMove(_, opponent_move): &GameMove) -> f64 {
     *opponent_move
@@ -10,18 +10,18 @@ pub fn to_my_move(GameMove(my_move, _): &GameMove) -> f64 {
     *my_move
 }
 
-pub fn is_cooperation(m: &f64) -> bool {
-    let p = random::<f64>();
+pub fn is_cooperation(m: &f64, rng: &mut impl Rng) -> bool {
+    let p = rng.gen::<f64>();
     m < &p
 }
 
-pub fn is_defection(m: &f64) -> bool {
-    let p = random::<f64>();
+pub fn is_defection(m: &f64, rng: &mut impl Rng) -> bool {
+    let p = rng.gen::<f64>();
     m >= &p
 }
 
-pub fn to_nearest_move(m: f64) -> f64 {
-    if is_cooperation(&m) {
+pub fn to_nearest_move(m: f64, rng: &mut impl Rng) -> f64 {
+    if is_cooperation(&m, rng) {
         COOPERATE
     } else {
         DEFECT
@@ -31,3 +31,38 @@ pub fn to_nearest_move(m: f64) -> f64 {
 pub fn to_opposite(m: f64) -> f64 {
     1.0 - m
 }
+
+pub fn to_move(defected: bool) -> f64 {
+    if defected {
+        DEFECT
+    } else {
+        COOPERATE
+    }
+}
+
+/// Whether the opponent's move in `round` reads as a defection, following `ctx.interpretation`
+pub fn opponent_defected(history: &GameHistory, round: usize, ctx: &mut Context) -> bool {
+    let interpretation = ctx.interpretation;
+    interpretation.is_defection(history[..=round].iter().map(to_opponent_move), &mut ctx.rng)
+}
+
+/// Whether my own move in `round` reads as a defection, following `ctx.interpretation`
+pub fn i_defected(history: &GameHistory, round: usize, ctx: &mut Context) -> bool {
+    let interpretation = ctx.interpretation;
+    interpretation.is_defection(history[..=round].iter().map(to_my_move), &mut ctx.rng)
+}
+
+/// The opponent's last move as a pure move, `None` before the first round
+pub fn read_last_opponent_move(history: &GameHistory, ctx: &mut Context) -> Option<f64> {
+    let last = history.len().checked_sub(1)?;
+    Some(to_move(opponent_defected(history, last, ctx)))
+}
+
+/// Every move of the opponent in order, read as whether it is a defection
+pub fn read_opponent_moves<'a>(
+    history: &'a GameHistory,
+    ctx: &'a mut Context,
+) -> impl Iterator<Item = bool> + 'a {
+    let interpretation = ctx.interpretation;
+    interpretation.read(history.iter().map(to_opponent_move), &mut ctx.rng)
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    payoff::Payoff,
+    registry::{self, to_id, Family, Registered, Template},
+    Context, GameHistory, GameMove, Strategy, COOPERATE, DEFECT,
+};
+
+use super::{memory_one::MemoryOne, utils};
+
+// Press & Dyson, "Iterated Prisoner's Dilemma contains strategies that dominate any evolutionary opponent" (2012)
+// Stewart & Plotkin, "From extortion to generosity, evolution in the Iterated Prisoner's Dilemma" (2013)
+pub fn all() -> Vec<Registered> {
+    presets()
+        .into_iter()
+        .map(|(name, strategy)| register(&to_id(name), name, strategy))
+        .collect()
+}
+
+/// `extort`, `zd-gtft` and their continuous variants, `phi` has to suit the tournament's payoff
+pub fn templates() -> Vec<Template> {
+    VARIANTS
+        .iter()
+        .map(|&(id, kind, continuous)| {
+            Template::new(id, move |id: &str, name: &str, values: &[f64]| {
+                let strategy = ZeroDeterminant {
+                    kind,
+                    chi: values[0],
+                    phi: values[1],
+                    continuous,
+                };
+                register(id, name, strategy)
+            })
+            .parameter("chi", 2.0, 1.0..=100.0)
+            .parameter("phi", 0.1, 0.0..=1.0)
+        })
+        .collect()
+}
+
+/// Template id, kind and whether it is continuous of every variant that can be built from a spec
+const VARIANTS: [(&str, ZeroDeterminantKind, bool); 4] = [
+    ("extort", ZeroDeterminantKind::Extortionate, false),
+    ("extort-continuous", ZeroDeterminantKind::Extortionate, true),
+    ("zd-gtft", ZeroDeterminantKind::Generous, false),
+    ("zd-gtft-continuous", ZeroDeterminantKind::Generous, true),
+];
+
+fn register(id: &str, name: &str, strategy: ZeroDeterminant) -> Registered {
+    let (description, author) = match strategy.kind {
+        ZeroDeterminantKind::Extortionate => (
+            "Memory-one, enforces mine - P = chi * (theirs - P) whatever the opponent does",
+            "Press & Dyson",
+        ),
+        ZeroDeterminantKind::Generous => (
+            "Memory-one, enforces mine - R = chi * (theirs - R) whatever the opponent does",
+            "Stewart & Plotkin",
+        ),
+    };
+    let family = if strategy.continuous {
+        Family::Continuous
+    } else {
+        Family::Classic
+    };
+
+    let registered = Registered::new(id, name, family, Box::new(strategy))
+        .description(description)
+        .author(author)
+        .parameter("chi", strategy.chi)
+        .parameter("phi", strategy.phi)
+        .memory(1);
+    if strategy.continuous {
+        registered
+    } else {
+        registered.stochastic()
+    }
+}
+
+/// The zero-determinant strategies entered by default, `phi` suits the default payoff
+pub fn presets() -> Vec<(&'static str, ZeroDeterminant)> {
+    vec![
+        ("Extort-2", ZeroDeterminant::extortionate(2.0, 0.1, false)),
+        (
+            "Extort-2 Continuous",
+            ZeroDeterminant::extortionate(2.0, 0.1, true),
+        ),
+        ("ZD-GTFT-2", ZeroDeterminant::generous(2.0, 0.1, false)),
+        (
+            "ZD-GTFT-2 Continuous",
+            ZeroDeterminant::generous(2.0, 0.1, true),
+        ),
+    ]
+}
+
+/// The zero-determinant strategy entered under `name`, either a preset or a spec such as `extort(chi=3)`, if any
+pub fn find(name: &str) -> Option<ZeroDeterminant> {
+    presets()
+        .into_iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, strategy)| strategy)
+        .or_else(|| {
+            let (template, _, values) = registry::parse(name).ok()?;
+            let &(_, kind, continuous) = VARIANTS.iter().find(|(id, _, _)| *id == template.id)?;
+            Some(ZeroDeterminant {
+                kind,
+                chi: values[0],
+                phi: values[1],
+                continuous,
+            })
+        })
+}
+
+/// Which score the enforced relation is anchored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZeroDeterminantKind {
+    /// `mine - P = chi * (theirs - P)`, the opponent can only raise my score more than theirs
+    Extortionate,
+    /// `mine - R = chi * (theirs - R)`, I lose less than the opponent whenever we fall short of mutual cooperation
+    Generous,
+}
+
+/// A memory-one strategy that enforces a linear relation between both players' average scores
+/// The response to every outcome follows from the payoff in the `Context`, so it adapts to the tournament's point values
+/// The classic variant plays pure moves with the derived probabilities (reading continuous moves following `Context::interpretation`),
+/// the continuous variant plays its defection probability as a move, blending the four outcomes like `MemoryOne`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ZeroDeterminant {
+    pub kind: ZeroDeterminantKind,
+    /// Slope of the relation, at least 1
+    pub chi: f64,
+    /// Scale of the strategy, only valid while every derived probability is between 0 and 1
+    pub phi: f64,
+    pub continuous: bool,
+}
+
+impl ZeroDeterminant {
+    pub fn extortionate(chi: f64, phi: f64, continuous: bool) -> Self {
+        ZeroDeterminant {
+            kind: ZeroDeterminantKind::Extortionate,
+            chi,
+            phi,
+            continuous,
+        }
+    }
+
+    pub fn generous(chi: f64, phi: f64, continuous: bool) -> Self {
+        ZeroDeterminant {
+            kind: ZeroDeterminantKind::Generous,
+            chi,
+            phi,
+            continuous,
+        }
+    }
+
+    /// The score both sides of the relation are measured from
+    pub fn baseline(&self, payoff: &Payoff) -> f64 {
+        match self.kind {
+            ZeroDeterminantKind::Extortionate => payoff.punishment,
+            ZeroDeterminantKind::Generous => payoff.reward,
+        }
+    }
+
+    /// Chance of cooperating after CC, CD, DC and DD (my move first), before clamping
+    pub fn cooperation(&self, payoff: &Payoff) -> [f64; 4] {
+        let Payoff {
+            reward: r,
+            sucker: s,
+            temptation: t,
+            punishment: p,
+        } = *payoff;
+        let b = self.baseline(payoff);
+
+        // p - (1, 1, 0, 0) = phi * ((S_X - B) - chi * (S_Y - B))
+        let mine = [r, s, t, p];
+        let theirs = [r, t, s, p];
+        let keep = [1.0, 1.0, 0.0, 0.0];
+
+        std::array::from_fn(|i| keep[i] + self.phi * ((mine[i] - b) - self.chi * (theirs[i] - b)))
+    }
+
+    pub fn validate(&self, payoff: &Payoff) -> Result<(), String> {
+        if self.chi < 1.0 {
+            return Err(String::from("chi must be at least 1"));
+        }
+        if self.phi <= 0.0 {
+            return Err(String::from("phi must be positive"));
+        }
+        if self
+            .cooperation(payoff)
+            .iter()
+            .any(|p| !(0.0..=1.0).contains(p))
+        {
+            return Err(format!(
+                "phi = {} is too large for chi = {} with this payoff",
+                self.phi, self.chi
+            ));
+        }
+        Ok(())
+    }
+
+    /// Responses as defection values, probabilities outside [0, 1] are clamped
+    pub fn memory_one(&self, payoff: &Payoff) -> MemoryOne {
+        let [cc, cd, dc, dd] = self.cooperation(payoff).map(|p| 1.0 - p.clamp(0.0, 1.0));
+        MemoryOne::new(COOPERATE, cc, dc, cd, dd)
+    }
+
+    /// How far a match is from the enforced relation, in points per round: zero when it holds exactly
+    pub fn residual(&self, payoff: &Payoff, mine: f64, theirs: f64) -> f64 {
+        let b = self.baseline(payoff);
+        (mine - b) - self.chi * (theirs - b)
+    }
+}
+
+impl Strategy for ZeroDeterminant {
+    fn next_move(
+        &mut self,
+        last_move: Option<GameMove>,
+        history: &GameHistory,
+        ctx: &mut Context,
+    ) -> f64 {
+        let mut responses = self.memory_one(&ctx.payoff);
+        if self.continuous {
+            return responses.next_move(last_move, history, ctx);
+        }
+
+        let Some(last) = history.len().checked_sub(1) else {
+            return COOPERATE;
+        };
+        let outcome = GameMove(
+            utils::to_move(utils::i_defected(history, last, ctx)),
+            utils::to_move(utils::opponent_defected(history, last, ctx)),
+        );
+
+        let defection = responses.respond(&outcome);
+        if utils::is_defection(&defection, &mut ctx.rng) {
+            DEFECT
+        } else {
+            COOPERATE
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    strategies::memory_one::MemoryOne,
+    tournament::{score_against, TournamentConfig},
+    NamedStrategy, Strategy,
+};
+
+/// One of the five values of a `MemoryOne`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SweepParameter {
+    Opening,
+    CC,
+    DC,
+    CD,
+    DD,
+}
+
+/// A grid of `MemoryOne` variants: `base` with two of its values stepped evenly from 0 to 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepConfig {
+    pub x: SweepParameter,
+    pub y: SweepParameter,
+    /// Values per parameter, both ends included
+    pub steps: usize,
+    pub base: MemoryOne,
+}
+
+/// Score of every variant, rows follow `y` and columns follow `x`
+#[derive(Debug, Clone, Default)]
+pub struct SweepResult {
+    pub x_values: Vec<f64>,
+    pub y_values: Vec<f64>,
+    pub scores: Vec<f64>,
+}
+
+impl SweepParameter {
+    /// Parses `opening`, `cc`, `dc`, `cd` or `dd`
+    pub fn parse(spec: &str) -> Result<SweepParameter, String> {
+        match spec.trim().to_lowercase().as_str() {
+            "opening" => Ok(SweepParameter::Opening),
+            "cc" => Ok(SweepParameter::CC),
+            "dc" => Ok(SweepParameter::DC),
+            "cd" => Ok(SweepParameter::CD),
+            "dd" => Ok(SweepParameter::DD),
+            _ => Err(format!(
+                "expected opening, cc, dc, cd or dd, got '{}'",
+                spec
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SweepParameter::Opening => "opening",
+            SweepParameter::CC => "cc",
+            SweepParameter::DC => "dc",
+            SweepParameter::CD => "cd",
+            SweepParameter::DD => "dd",
+        }
+    }
+
+    fn set(&self, strategy: &mut MemoryOne, value: f64) {
+        match self {
+            SweepParameter::Opening => strategy.opening = value,
+            SweepParameter::CC => strategy.cc = value,
+            SweepParameter::DC => strategy.dc = value,
+            SweepParameter::CD => strategy.cd = value,
+            SweepParameter::DD => strategy.dd = value,
+        }
+    }
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        SweepConfig {
+            x: SweepParameter::CD,
+            y: SweepParameter::DD,
+            steps: 11,
+            base: MemoryOne::tit_for_tat(),
+        }
+    }
+}
+
+impl SweepConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.x == self.y {
+            return Err(String::from("the two swept parameters must differ"));
+        }
+        if self.steps < 2 {
+            return Err(String::from("steps must be at least 2"));
+        }
+        self.base.validate()
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.steps)
+            .map(|i| i as f64 / (self.steps - 1) as f64)
+            .collect()
+    }
+
+    /// Every variant, row by row
+    pub fn variants(&self) -> Vec<MemoryOne> {
+        let values = self.values();
+
+        values
+            .iter()
+            .flat_map(|&y| values.iter().map(move |&x| (x, y)))
+            .map(|(x, y)| {
+                let mut variant = self.base;
+                self.x.set(&mut variant, x);
+                self.y.set(&mut variant, y);
+                variant
+            })
+            .collect()
+    }
+}
+
+impl SweepResult {
+    pub fn score(&self, row: usize, col: usize) -> f64 {
+        self.scores[row * self.x_values.len() + col]
+    }
+
+    /// Row and column of the highest score
+    pub fn best(&self) -> Option<(usize, usize)> {
+        let best =
+            (0..self.scores.len()).max_by(|&a, &b| self.scores[a].total_cmp(&self.scores[b]))?;
+        Some((best / self.x_values.len(), best % self.x_values.len()))
+    }
+}
+
+/// Scores every variant by its points per round against the entered strategies
+pub async fn run_sweep(
+    strategies: Vec<NamedStrategy>,
+    config: &TournamentConfig,
+    sweep: &SweepConfig,
+) -> Result<SweepResult, String> {
+    sweep.validate()?;
+
+    let candidates = sweep
+        .variants()
+        .into_iter()
+        .map(|variant| Box::new(variant) as Box<dyn Strategy>)
+        .collect();
+    let scores = score_against(candidates, strategies, config, "sweep").await?;
+
+    Ok(SweepResult {
+        x_values: sweep.values(),
+        y_values: sweep.values(),
+        scores,
+    })
+}
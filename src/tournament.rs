@@ -0,0 +1,271 @@
+use std::{collections::HashSet, sync::Arc};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ecology::EcologyConfig,
+    interpolation::{Bilinear, PayoffInterpolation},
+    interpretation::Interpretation,
+    length::MatchLength,
+    noise::Noise,
+    payoff::Payoff,
+    play_matchup, play_strategies,
+    registry::{self, registry},
+    strategies::zero_determinant,
+    GameResult, MatchupResult, NamedStrategy, Strategy, NUM_ROUNDS,
+};
+
+/// Everything needed to reproduce a round robin tournament
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    pub rounds: usize,
+    /// How many times each pairing is played, results are averaged over repetitions
+    pub repetitions: usize,
+    pub payoff: Payoff,
+    #[serde(with = "crate::interpolation::spec")]
+    pub interpolation: Arc<dyn PayoffInterpolation>,
+    /// How strategies built on pure moves read continuous moves
+    pub interpretation: Interpretation,
+    pub noise: Noise,
+    /// Fixed, hidden or geometric match lengths, `rounds` is the length of fixed and hidden matches
+    pub match_length: MatchLength,
+    /// Weight factor per round for the discounted score, defaults to the continuation chance of geometric matches
+    pub discount: Option<f64>,
+    /// Replicator dynamics run on the results, shown in the viewer's population panel
+    pub ecology: EcologyConfig,
+    /// Seeds every strategy and the engine, `None` picks a fresh seed every run
+    pub seed: Option<u64>,
+    /// Names of the strategies to enter, `None` enters every known strategy
+    pub included_strategies: Option<Vec<String>>,
+    /// Whether strategies also play against a copy of themselves
+    pub self_play: bool,
+    /// Whether strategies are told the id of their opponent before every match
+    pub reveal_opponents: bool,
+    /// Whether strategies keep what they learned from one repetition of a pairing to the next instead of starting fresh
+    /// Only the repetitions of the round robin carry over, `score_against` and the spatial game play fresh clones every match
+    pub carry_over: bool,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        TournamentConfig {
+            rounds: NUM_ROUNDS,
+            repetitions: 1,
+            payoff: Payoff::default(),
+            interpolation: Arc::new(Bilinear),
+            interpretation: Interpretation::default(),
+            noise: Noise::default(),
+            match_length: MatchLength::default(),
+            discount: None,
+            ecology: EcologyConfig::default(),
+            seed: None,
+            included_strategies: None,
+            self_play: true,
+            reveal_opponents: false,
+            carry_over: false,
+        }
+    }
+}
+
+impl TournamentConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rounds == 0 {
+            return Err(String::from("rounds must be at least 1"));
+        }
+        if self.repetitions == 0 {
+            return Err(String::from("repetitions must be at least 1"));
+        }
+        if let Some(discount) = self.discount {
+            if !(discount > 0.0 && discount <= 1.0) {
+                return Err(String::from("discount must be above 0 and at most 1"));
+            }
+        }
+        self.interpretation.validate()?;
+        self.match_length.validate()?;
+        self.ecology.validate()?;
+        self.noise.validate()
+    }
+
+    /// Discount used for the discounted score, `None` when it is not computed
+    pub fn discount_factor(&self) -> Option<f64> {
+        self.discount.or(self.match_length.continuation())
+    }
+
+    /// Replaces a missing seed with a random one, so that the run can be replayed later
+    pub fn with_resolved_seed(mut self) -> Self {
+        self.seed.get_or_insert_with(rand::random);
+        self
+    }
+
+    /// The strategies entered into this tournament, in the order they were listed (by id, name or spec, as `registry::resolve` reads them)
+    pub fn resolve_strategies(&self) -> Result<Vec<NamedStrategy>, String> {
+        let entered: Vec<NamedStrategy> = match &self.included_strategies {
+            None => registry().into_iter().map(NamedStrategy::from).collect(),
+            Some(names) => names
+                .iter()
+                .map(|name| registry::resolve(name).map(NamedStrategy::from))
+                .collect::<Result<_, _>>()?,
+        };
+
+        // results are keyed by name, so a strategy entered twice (even by its id and its name) would be ambiguous
+        let mut seen = HashSet::new();
+        if let Some((name, _)) = entered.iter().find(|(name, _)| !seen.insert(name)) {
+            return Err(format!("'{}' is entered more than once", name));
+        }
+        // the spatial lattice and the population simulations need at least one strategy to place
+        if entered.is_empty() {
+            return Err(String::from("no strategies were entered"));
+        }
+        // zero-determinant strategies only enforce their relation while `phi` suits the payoff
+        for (name, _) in &entered {
+            if let Some(strategy) = zero_determinant::find(name) {
+                strategy
+                    .validate(&self.payoff)
+                    .map_err(|e| format!("{}: {}", name, e))?;
+            }
+        }
+        Ok(entered)
+    }
+
+    /// How many matchups `run_competition` plays between `num_strategies` strategies
+    pub fn matchup_count(&self, num_strategies: usize) -> usize {
+        if self.self_play {
+            num_strategies * num_strategies
+        } else {
+            num_strategies * num_strategies.saturating_sub(1)
+        }
+    }
+
+    /// Where the result of `first` playing `second` is stored in the output of `run_competition`
+    pub fn matchup_index(
+        &self,
+        num_strategies: usize,
+        first: usize,
+        second: usize,
+    ) -> Option<usize> {
+        if self.self_play {
+            Some(first * num_strategies + second)
+        } else if first == second {
+            None
+        } else {
+            let second = if second > first { second - 1 } else { second };
+            Some(first * (num_strategies - 1) + second)
+        }
+    }
+
+    /// Rng for one repetition of one matchup, identical across runs when a seed is set
+    pub fn matchup_rng(&self, first_name: &str, second_name: &str, repetition: usize) -> StdRng {
+        match self.seed {
+            Some(seed) => {
+                StdRng::seed_from_u64(matchup_seed(seed, first_name, second_name, repetition))
+            }
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Rng for one run of a simulation built on this tournament, `stream` keeps different simulations apart
+    pub fn stream_rng(&self, stream: &str, index: usize) -> StdRng {
+        self.matchup_rng(stream, "", index)
+    }
+}
+
+/// Mean points per round of every candidate against every opponent, over the tournament's repetitions
+/// Candidates play in parallel, each seeded from `stream` and its index
+/// Every match is played by fresh clones, so nothing learned carries over whatever `carry_over` says
+pub async fn score_against(
+    candidates: Vec<Box<dyn Strategy>>,
+    opponents: Vec<NamedStrategy>,
+    config: &TournamentConfig,
+    stream: &str,
+) -> Result<Vec<f64>, String> {
+    let config = Arc::new(config.clone());
+    let mut tasks = vec![];
+
+    for (i, candidate) in candidates.into_iter().enumerate() {
+        let opponents = opponents.clone();
+        let config = Arc::clone(&config);
+        let mut rng = config.stream_rng(stream, i);
+
+        tasks.push(tokio::spawn(async move {
+            let mut total = 0.0;
+            let mut matches = 0;
+
+            for (name, opponent) in &opponents {
+                for _ in 0..config.repetitions {
+                    let mut first = candidate.clone();
+                    let mut second = opponent.clone();
+                    let mut match_rng = StdRng::seed_from_u64(rng.gen());
+
+                    let (GameResult(points, _), history, _) = play_strategies(
+                        &mut first,
+                        &mut second,
+                        (None, Some(name)),
+                        &config,
+                        &mut match_rng,
+                    );
+                    total += points / history.len().max(1) as f64;
+                    matches += 1;
+                }
+            }
+
+            total / matches.max(1) as f64
+        }));
+    }
+
+    let mut scores = vec![];
+    for task in tasks {
+        scores.push(task.await.map_err(|e| e.to_string())?);
+    }
+    Ok(scores)
+}
+
+/// Plays a single matchup of this tournament again, which reproduces the original history when the seed is set
+pub fn replay_matchup(
+    config: &TournamentConfig,
+    first_name: &str,
+    second_name: &str,
+) -> Result<MatchupResult, String> {
+    let first =
+        find_strategy(first_name).ok_or_else(|| format!("unknown strategy '{}'", first_name))?;
+    let second =
+        find_strategy(second_name).ok_or_else(|| format!("unknown strategy '{}'", second_name))?;
+
+    Ok(play_matchup(
+        first_name.to_string(),
+        first,
+        second_name.to_string(),
+        second,
+        config,
+    ))
+}
+
+/// The strategy with this id or name
+pub fn find_strategy(name: &str) -> Option<Box<dyn Strategy>> {
+    registry::find(name).map(|registered| registered.strategy)
+}
+
+pub fn all_strategies() -> Vec<NamedStrategy> {
+    registry().into_iter().map(NamedStrategy::from).collect()
+}
+
+/// Mixes the names rather than indices so that a matchup keeps its seed when other strategies are excluded
+fn matchup_seed(seed: u64, first_name: &str, second_name: &str, repetition: usize) -> u64 {
+    // FNV-1a, stable across platforms and compiler versions unlike `DefaultHasher`
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let bytes = seed
+        .to_le_bytes()
+        .into_iter()
+        .chain(first_name.bytes())
+        .chain([0])
+        .chain(second_name.bytes())
+        .chain([0])
+        .chain((repetition as u64).to_le_bytes());
+
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
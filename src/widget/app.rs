@@ -1,40 +1,57 @@
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use iced::{
-    widget::{button, column, container, row, text, Space},
+    time,
+    widget::{button, canvas, checkbox, column, container, row, text, Space},
     window::{get_latest, maximize},
-    Alignment, Color, Element, Length, Task,
+    Alignment, Color, Element, Length, Subscription, Task,
 };
 use indexmap::IndexMap;
 use rayon::prelude::*;
 
 use crate::{
     colors::blend_colors,
+    csv::export_tournament,
+    ecology::{payoff_matrix, simulate, Population},
+    registry::{self, StrategyInfo},
     run_competition,
-    strategies::{classic, continuous, tsvrn9},
-    GameResult, MatchupResult,
+    session::{load_session, save_session, Session, SESSION_EXTENSION},
+    stats::{calculate_stats, Stat},
+    strategies::scripted::{self, SCRIPT_DIR},
+    tournament::{replay_matchup, TournamentConfig},
+    GameHistory, MatchupResult,
 };
 
 use super::{
     grid::{Grid, GridMessage},
-    inspectors::{MatchInspector, MatchInspectorMessage},
+    inspectors::{MatchInspector, MatchInspectorMessage, ReplayedMatchup},
     labels::{LabelList, LabelListMessage},
+    plot::LinePlot,
 };
 
+// there is only ever one inspector, so boxing `State` to shrink the enum would buy nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(Default)]
 pub enum ResultsInspector {
     #[default]
     Loading,
     Raw(RawState),
     Loaded(State),
+    Failed(String),
 }
 
 pub struct State {
     data: Data,
 
-    selected_stat: &'static str,
+    selected_stat: String,
     filters: Vec<StatFilter>,
     colors: Colors,
+    /// One color per strategy for the swatches in the label list and the population plot
+    palette: Vec<Color>,
     cell_size: u16,
 
     grid: Grid,
@@ -42,20 +59,60 @@ pub struct State {
 
     selected_cell: Option<(usize, usize)>,
     match_inspector: MatchInspector,
+
+    show_population: bool,
+    population: Option<Result<Population, String>>,
+
+    /// Registry entry of every strategy, `None` for strategies that are no longer registered
+    strategy_infos: Vec<Option<StrategyInfo>>,
+    /// Label last clicked, shown in the inspector until a cell is clicked
+    inspected_strategy: Option<usize>,
+
+    export_history: bool,
+    status: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct RawState {
     data: Data,
-    selected_stat: &'static str,
+    selected_stat: String,
     filters: Vec<StatFilter>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Data {
-    strategy_names: Vec<&'static str>,
+    config: TournamentConfig,
+    strategy_names: Vec<String>,
     matchup_results: Vec<MatchupResult>,
-    stats: IndexMap<&'static str, Arc<Stat>>,
+    stats: IndexMap<String, Arc<Stat>>,
+    /// Scripts in `SCRIPT_DIR` when the tournament was run, `None` for opened sessions, which are never rerun
+    scripts: Option<Vec<(PathBuf, Option<SystemTime>)>>,
+    /// First error of every strategy file that failed to load, or script that failed to play
+    strategy_errors: Vec<(String, String)>,
+}
+
+impl From<Session> for Data {
+    fn from(session: Session) -> Self {
+        Data {
+            stats: session.stats(),
+            config: session.config,
+            strategy_names: session.strategy_names,
+            matchup_results: session.matchup_results,
+            scripts: None,
+            strategy_errors: vec![],
+        }
+    }
+}
+
+impl Data {
+    fn to_session(&self) -> Session {
+        Session::new(
+            self.config.clone(),
+            self.strategy_names.clone(),
+            self.matchup_results.clone(),
+            &self.stats,
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -64,12 +121,6 @@ pub struct Colors {
     strategy_colors: Vec<Color>,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct Stat {
-    values: Vec<f64>,
-    strategy_averages: Vec<f64>,
-}
-
 #[derive(Debug, Clone)]
 pub enum StatFilter {
     HideRow(usize),
@@ -78,6 +129,7 @@ pub enum StatFilter {
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    Simulated(Result<Data, String>),
     Raw(Data),
     RecalculateColor,
     Loaded(Colors),
@@ -85,51 +137,134 @@ pub enum Message {
     LabelListMessage(LabelListMessage),
     MatchInspectorMessage(MatchInspectorMessage),
     CycleSelectedStat,
+    ToggleExportHistory(bool),
+    Export,
+    Exported(Result<Vec<PathBuf>, String>),
+    Open,
+    Opened(Result<Option<Data>, String>),
+    Save,
+    Saved(Result<Option<PathBuf>, String>),
+    TogglePopulation,
+    PopulationSimulated(Result<Population, String>),
+    CheckScripts,
+}
+
+impl State {
+    fn selected_matchup(&self) -> Option<&MatchupResult> {
+        let n = self.data.strategy_names.len();
+        let (x, y) = self.selected_cell?;
+        let index = self.data.config.matchup_index(n, x, y)?;
+        self.data.matchup_results.get(index)
+    }
 }
 
 impl ResultsInspector {
-    pub fn new() -> (ResultsInspector, Task<Message>) {
-        (Self::Loading, Task::perform(load(), Message::Raw))
+    pub fn new(config: TournamentConfig) -> (ResultsInspector, Task<Message>) {
+        (
+            Self::Loading,
+            Task::perform(load(config), Message::Simulated),
+        )
+    }
+
+    /// Skips the simulation and shows a saved session
+    pub fn open(path: PathBuf) -> (ResultsInspector, Task<Message>) {
+        (
+            Self::Loading,
+            Task::perform(open_session(path), |result| {
+                Message::Opened(result.map(Some))
+            }),
+        )
+    }
+
+    /// Watches the scripts of a simulated tournament, so that it is rerun when one is added, removed or saved
+    pub fn subscription(&self) -> Subscription<Message> {
+        match self {
+            Self::Loaded(state) if state.data.scripts.is_some() => {
+                time::every(SCRIPT_CHECK_INTERVAL).map(|_| Message::CheckScripts)
+            }
+            _ => Subscription::none(),
+        }
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            Message::Opened(result) => self.update_opened(result),
+            Message::Simulated(result) => self.update_simulated(result),
             Message::Raw(_) | Message::RecalculateColor | Message::Loaded(_) => {
                 self.update_transition_states(message)
             }
-            _ => match &self {
+            Message::GridMessage(_)
+            | Message::LabelListMessage(_)
+            | Message::MatchInspectorMessage(_)
+            | Message::CycleSelectedStat
+            | Message::ToggleExportHistory(_)
+            | Message::Export
+            | Message::Exported(_)
+            | Message::Open
+            | Message::Save
+            | Message::Saved(_)
+            | Message::TogglePopulation
+            | Message::PopulationSimulated(_)
+            | Message::CheckScripts => match &self {
                 Self::Loaded(_) => self.update_loaded_state(message),
-                _ => panic!("Invalid State"),
+                // a task started before the scripts were reloaded can still reply, its results are gone by then
+                _ => ignore(&message),
             },
         }
     }
 
+    fn update_opened(&mut self, result: Result<Option<Data>, String>) -> Task<Message> {
+        match result {
+            Ok(Some(data)) => {
+                *self = ResultsInspector::Loading;
+                self.update(Message::Raw(data))
+            }
+            Ok(None) => Task::none(),
+            Err(e) => {
+                if let ResultsInspector::Loaded(state) = self {
+                    state.status = Some(format!("Open failed: {}", e));
+                } else {
+                    *self = ResultsInspector::Failed(e);
+                }
+                Task::none()
+            }
+        }
+    }
+
+    fn update_simulated(&mut self, result: Result<Data, String>) -> Task<Message> {
+        match result {
+            Ok(data) => self.update(Message::Raw(data)),
+            Err(e) => {
+                *self = ResultsInspector::Failed(format!("Simulation failed: {}", e));
+                Task::none()
+            }
+        }
+    }
+
     fn update_transition_states(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Raw(data) => {
                 if let ResultsInspector::Loading = self {
                     *self = ResultsInspector::Raw(RawState {
-                        selected_stat: data.stats.keys().next().unwrap(),
+                        selected_stat: data.stats.keys().next().unwrap().clone(),
                         data,
                         filters: vec![],
                     });
 
                     self.update(Message::RecalculateColor)
                 } else {
-                    panic!("Unexpected message");
+                    ignore(&Message::Raw(data))
                 }
             }
             Message::RecalculateColor => {
                 let stat = match self {
                     ResultsInspector::Loaded(state) => {
-                        let selected_stat = state.selected_stat;
-                        Arc::clone(&state.data.stats[selected_stat])
+                        Arc::clone(&state.data.stats[&state.selected_stat])
                     }
                     ResultsInspector::Raw(raw_state) => {
-                        let selected_stat = raw_state.selected_stat;
-                        Arc::clone(&raw_state.data.stats[selected_stat])
+                        Arc::clone(&raw_state.data.stats[&raw_state.selected_stat])
                     }
-                    _ => panic!("Unexpected state"),
+                    _ => return ignore(&Message::RecalculateColor),
                 };
 
                 Task::perform(calculate_cell_and_strategy_colors(stat), Message::Loaded)
@@ -139,8 +274,9 @@ impl ResultsInspector {
                     let n = raw_state.data.strategy_names.len();
 
                     let mut new_state = State {
-                        selected_stat: raw_state.selected_stat,
+                        selected_stat: std::mem::take(&mut raw_state.selected_stat),
                         colors,
+                        palette: crate::colors::strategy_palette(n),
                         cell_size: 30,
                         grid: Grid::new(n, n, true),
                         label_list: Default::default(),
@@ -148,6 +284,12 @@ impl ResultsInspector {
                         filters: Default::default(),
                         selected_cell: Default::default(),
                         match_inspector: Default::default(),
+                        show_population: false,
+                        population: None,
+                        strategy_infos: registry::infos(&raw_state.data.strategy_names),
+                        inspected_strategy: None,
+                        export_history: false,
+                        status: None,
                     };
 
                     std::mem::swap(&mut new_state.data, &mut raw_state.data);
@@ -160,9 +302,10 @@ impl ResultsInspector {
                     state.colors = colors;
                     Task::none()
                 }
-                _ => panic!("Unexpected State"),
+                // colors of the results shown before a reload
+                _ => ignore(&Message::Loaded(colors)),
             },
-            _ => panic!("Not a transitional state"),
+            _ => unreachable!("Not a transitional state"),
         }
     }
 
@@ -170,13 +313,19 @@ impl ResultsInspector {
         match self {
             Self::Loaded(state) => match message {
                 Message::Raw(_) | Message::RecalculateColor | Message::Loaded(_) => {
-                    panic!("Not a loaded state");
+                    unreachable!("Handled before dispatch");
                 }
                 Message::GridMessage(grid_message) => {
                     match grid_message {
                         GridMessage::Focus(x, y) => {
                             let previous_cell = state.selected_cell;
                             state.selected_cell = Some((x, y));
+                            state.inspected_strategy = None;
+
+                            let matchup_result = state.selected_matchup().cloned();
+                            state
+                                .match_inspector
+                                .inspect(matchup_result.as_ref(), &state.data.config);
 
                             if let Some((x_previous, y_previous)) = previous_cell {
                                 state
@@ -194,28 +343,128 @@ impl ResultsInspector {
                     state.grid.update(grid_message);
                     Task::none()
                 }
-                Message::LabelListMessage(label_list_message) => match label_list_message {
-                    LabelListMessage::Focus(_) => Task::none(), // maybe implement functionality eventually
-                    LabelListMessage::Unfocus(_) => Task::none(),
-                },
+                Message::LabelListMessage(label_list_message) => {
+                    // focused labels are highlighted in the population plot
+                    match label_list_message {
+                        LabelListMessage::Focus(index) => state.inspected_strategy = Some(index),
+                        LabelListMessage::Unfocus(index) => {
+                            if state.inspected_strategy == Some(index) {
+                                state.inspected_strategy = None;
+                            }
+                        }
+                    }
+                    state.label_list.update(label_list_message);
+                    Task::none()
+                }
                 Message::MatchInspectorMessage(message) => {
+                    let task = match (&message, state.selected_matchup()) {
+                        (MatchInspectorMessage::Replay, Some(matchup_result)) => {
+                            let matchup = ReplayedMatchup::of(matchup_result);
+                            Task::perform(
+                                replay(state.data.config.clone(), matchup_result.clone()),
+                                move |result| {
+                                    Message::MatchInspectorMessage(MatchInspectorMessage::Replayed(
+                                        matchup.clone(),
+                                        result,
+                                    ))
+                                },
+                            )
+                        }
+                        _ => Task::none(),
+                    };
+
                     state.match_inspector.update(message);
+                    task
+                }
+                Message::TogglePopulation => {
+                    state.show_population = !state.show_population;
+                    if !state.show_population || state.population.is_some() {
+                        return Task::none();
+                    }
+
+                    let data = &state.data;
+                    let n = data.strategy_names.len();
+                    let matrix = payoff_matrix(&data.matchup_results, &data.config, n);
+                    let ecology = data.config.ecology.clone();
+
+                    Task::perform(
+                        async move { simulate(&matrix, n, &ecology) },
+                        Message::PopulationSimulated,
+                    )
+                }
+                Message::PopulationSimulated(result) => {
+                    state.population = Some(result);
                     Task::none()
                 }
                 Message::CycleSelectedStat => {
                     let stats = &state.data.stats;
 
-                    let index = stats.get_index_of(state.selected_stat).unwrap();
-                    state.selected_stat = stats.get_index((index + 1) % stats.len()).unwrap().0;
+                    let index = stats.get_index_of(&state.selected_stat).unwrap();
+                    state.selected_stat = stats
+                        .get_index((index + 1) % stats.len())
+                        .unwrap()
+                        .0
+                        .clone();
 
                     self.update(Message::RecalculateColor)
                 }
-            }
-            _ => panic!("Invalid state")
+                Message::ToggleExportHistory(export_history) => {
+                    state.export_history = export_history;
+                    Task::none()
+                }
+                Message::Export => {
+                    state.status = Some(String::from("Exporting..."));
+                    Task::perform(
+                        export(state.data.clone(), state.export_history),
+                        Message::Exported,
+                    )
+                }
+                Message::Exported(result) => {
+                    state.status = Some(match result {
+                        Ok(paths) => format!("Exported {} files to {}", paths.len(), EXPORT_DIR),
+                        Err(e) => format!("Export failed: {}", e),
+                    });
+                    Task::none()
+                }
+                Message::Open => Task::perform(pick_and_open(), Message::Opened),
+                Message::Opened(_) | Message::Simulated(_) => {
+                    unreachable!("Handled before dispatch")
+                }
+                Message::Save => {
+                    Task::perform(pick_and_save(state.data.to_session()), Message::Saved)
+                }
+                Message::Saved(result) => {
+                    state.status = match result {
+                        Ok(Some(path)) => Some(format!("Saved session to {}", path.display())),
+                        Ok(None) => None,
+                        Err(e) => Some(format!("Save failed: {}", e)),
+                    };
+                    Task::none()
+                }
+                Message::CheckScripts => {
+                    let scripts = scripted::fingerprint(Path::new(SCRIPT_DIR));
+                    if state.data.scripts.as_ref() == Some(&scripts) {
+                        return Task::none();
+                    }
+
+                    // a strategy entered by id may be gone, keep the old results until it is back
+                    registry::reload();
+                    let config = state.data.config.clone();
+                    if let Err(e) = config.resolve_strategies() {
+                        state.data.scripts = Some(scripts);
+                        state.status = Some(format!("Reload failed: {}", e));
+                        return Task::none();
+                    }
+
+                    *self = ResultsInspector::Loading;
+                    Task::perform(load(config), Message::Simulated)
+                }
+            },
+            _ => unreachable!("Only called with loaded results"),
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         match self {
             ResultsInspector::Loading | ResultsInspector::Raw(_) => container(
                 text(if let ResultsInspector::Loading = self {
@@ -230,12 +479,17 @@ impl ResultsInspector {
             .center(Length::Fill)
             .into(),
             ResultsInspector::Loaded(state) => Self::view_loaded(state),
+            ResultsInspector::Failed(error) => container(text(error).size(24))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center(Length::Fill)
+                .into(),
         }
     }
 
-    fn view_loaded(state: &State) -> Element<Message> {
+    fn view_loaded(state: &State) -> Element<'_, Message> {
         let title = button(
-            text(state.selected_stat)
+            text(state.selected_stat.as_str())
                 .size(36)
                 .center()
                 .width(Length::Fill),
@@ -258,19 +512,18 @@ impl ResultsInspector {
         .width(Length::Fill)
         .on_press(Message::CycleSelectedStat);
 
-        let inspector = match state.selected_cell {
-            Some((x, y)) => {
-                let n = state.data.strategy_names.len();
-                state
-                    .match_inspector
-                    .view(
-                        state.data.matchup_results.get(x * n + y).unwrap(),
-                        None,
-                        state.cell_size,
-                    )
-                    .map(Message::MatchInspectorMessage)
-            }
-            None => Space::new(0, 0).into(),
+        let inspector = match (
+            state.show_population,
+            state.inspected_strategy,
+            state.selected_matchup(),
+        ) {
+            (true, _, _) => Self::view_population(state),
+            (false, Some(index), _) => Self::view_strategy_info(state, index),
+            (false, None, Some(matchup_result)) => state
+                .match_inspector
+                .view(matchup_result, None, state.cell_size)
+                .map(Message::MatchInspectorMessage),
+            (false, None, None) => Space::new(0, 0).into(),
         };
 
         let content = row!(
@@ -279,6 +532,7 @@ impl ResultsInspector {
                 .view(
                     &state.data.strategy_names,
                     &state.colors.strategy_colors,
+                    &state.palette,
                     state.cell_size,
                     iced::Alignment::End
                 )
@@ -294,94 +548,284 @@ impl ResultsInspector {
         .spacing(6)
         .padding(4);
 
-        column!(title, content)
+        let toolbar = row!(
+            button(text("Open...")).on_press(Message::Open),
+            button(text("Save...")).on_press(Message::Save),
+            button(text("Export CSV")).on_press(Message::Export),
+            checkbox("Include history", state.export_history)
+                .on_toggle(Message::ToggleExportHistory),
+            button(text(if state.show_population {
+                "Matchups"
+            } else {
+                "Population"
+            }))
+            .on_press(Message::TogglePopulation),
+            text(state.status.as_deref().unwrap_or_default()),
+        )
+        .spacing(12)
+        .align_y(Alignment::Center);
+
+        let strategy_errors = column(state.data.strategy_errors.iter().map(|(source, error)| {
+            text!("{}: {}", source, error)
+                .color(crate::colors::RED)
+                .into()
+        }));
+
+        column!(title, toolbar, strategy_errors, content)
             .align_x(Alignment::Center)
             .padding(4)
             .into()
     }
-}
 
-async fn load() -> Data {
-    let strategies: Vec<_> = vec![classic::all(), continuous::all(), tsvrn9::all()]
-        .into_iter()
-        .flatten()
-        .collect();
+    /// Population share of every strategy over the generations of the ecological simulation
+    fn view_population(state: &State) -> Element<'_, Message> {
+        let population = match &state.population {
+            None => return text("Simulating population...").size(24).into(),
+            Some(Err(e)) => return text!("Population failed: {}", e).size(24).into(),
+            Some(Ok(population)) => population,
+        };
 
-    let strategy_names = strategies.iter().map(|(name, _)| *name).collect();
-    let grid_width = strategies.len();
-    let matchup_results = run_competition(strategies).await;
-
-    let (point_difference, points_per_round) = tokio::join!(
-        calculate_stat(
-            |MatchupResult {
-                 overall_result: GameResult(a, b),
-                 ..
-             }| a - b,
-            &matchup_results,
-            grid_width
-        ),
-        calculate_stat(
-            |MatchupResult {
-                 overall_result: GameResult(a, _),
-                 ..
-             }| *a,
-            &matchup_results,
-            grid_width
+        let survivors = population
+            .final_shares()
+            .iter()
+            .filter(|&&share| share >= SURVIVAL_SHARE)
+            .count();
+
+        let title = column!(
+            text("Population").size(36),
+            text!(
+                "{} generations, {} of {} strategies above {}%",
+                population.shares.len() - 1,
+                survivors,
+                state.data.strategy_names.len(),
+                SURVIVAL_SHARE * 100.0
+            )
+            .size(18)
         )
-    );
+        .align_x(Alignment::Center);
 
-    let mut stats = IndexMap::new();
+        let plot = canvas(LinePlot {
+            values: &population.shares,
+            colors: &state.palette,
+            highlighted: state.label_list.selected(),
+        })
+        .width(Length::Fill)
+        .height(Length::Fill);
 
-    stats.insert("Points per round", Arc::new(points_per_round));
-    stats.insert("Point difference", Arc::new(point_difference));
+        column!(title, plot)
+            .align_x(Alignment::Center)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(4)
+            .into()
+    }
 
-    Data {
-        strategy_names,
-        matchup_results,
-        stats,
+    /// Registry entry of the strategy whose label was clicked
+    fn view_strategy_info(state: &State, index: usize) -> Element<'_, Message> {
+        let name = &state.data.strategy_names[index];
+        let Some(info) = &state.strategy_infos[index] else {
+            return text!("No details for {}", name).size(24).into();
+        };
+
+        let field = |label: &str, value: String| {
+            row!(text!("{}:", label).width(110), text(value))
+                .spacing(6)
+                .align_y(Alignment::Start)
+        };
+
+        let mut details = column!(
+            text(&info.name).size(36),
+            text(&info.id).size(18),
+            field("Family", info.family.to_string()),
+            field(
+                "Author",
+                if info.author.is_empty() {
+                    String::from("unknown")
+                } else {
+                    info.author.clone()
+                }
+            ),
+            field("Memory", info.memory.to_string()),
+            field(
+                "Stochastic",
+                String::from(if info.stochastic { "yes" } else { "no" })
+            ),
+        )
+        .spacing(6)
+        .width(Length::Fill)
+        .padding(4);
+
+        if !info.parameters.is_empty() {
+            details = details.push(field("Parameters", info.parameter_list()));
+        }
+        if !info.description.is_empty() {
+            details = details.push(text(&info.description));
+        }
+
+        details.into()
     }
 }
 
-async fn calculate_stat(
-    by: fn(&MatchupResult) -> f64,
-    results: &Vec<MatchupResult>,
-    grid_width: usize,
-) -> Stat {
-    let values: Vec<_> = results.iter().map(by).collect();
+const EXPORT_DIR: &str = "output";
+/// How often a simulated tournament looks for changed scripts
+const SCRIPT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Share below which a strategy counts as extinct in the population panel
+const SURVIVAL_SHARE: f64 = 0.001;
+/// How far cells that are not significantly different from the average are blended towards gray
+const INSIGNIFICANT_FADE: f32 = 0.6;
+
+async fn replay(config: TournamentConfig, original: MatchupResult) -> Result<GameHistory, String> {
+    replay_matchup(&config, &original.first_name, &original.second_name)
+        .map(|replayed| replayed.history)
+}
 
-    let strategy_averages = values
-        .chunks_exact(grid_width)
-        .map(|d| d.iter().sum::<f64>() / d.len() as f64)
-        .collect::<Vec<_>>();
+async fn open_session(path: PathBuf) -> Result<Data, String> {
+    load_session(&path)
+        .await
+        .map(Data::from)
+        .map_err(|e| e.to_string())
+}
+
+async fn pick_and_open() -> Result<Option<Data>, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .add_filter("Session", &[SESSION_EXTENSION])
+        .pick_file()
+        .await;
 
-    Stat {
-        values,
-        strategy_averages,
+    match handle {
+        Some(handle) => open_session(handle.path().to_path_buf()).await.map(Some),
+        None => Ok(None),
     }
 }
 
+async fn pick_and_save(session: Session) -> Result<Option<PathBuf>, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .add_filter("Session", &[SESSION_EXTENSION])
+        .set_file_name(format!("session.{}", SESSION_EXTENSION))
+        .save_file()
+        .await;
+
+    let Some(handle) = handle else {
+        return Ok(None);
+    };
+
+    let path = handle.path().to_path_buf();
+    save_session(&path, &session)
+        .await
+        .map(|_| Some(path))
+        .map_err(|e| e.to_string())
+}
+
+/// Drops a message that no longer applies, such as the reply of a task started for results that were since replaced
+fn ignore(message: &Message) -> Task<Message> {
+    let kind = match message {
+        Message::Simulated(_) => "simulation",
+        Message::Raw(_) => "results",
+        Message::RecalculateColor => "color recalculation",
+        Message::Loaded(_) => "colors",
+        Message::GridMessage(_) => "grid message",
+        Message::LabelListMessage(_) => "label message",
+        Message::MatchInspectorMessage(_) => "match inspector message",
+        Message::CycleSelectedStat => "stat change",
+        Message::ToggleExportHistory(_) => "export option",
+        Message::Export => "export",
+        Message::Exported(_) => "export result",
+        Message::Open => "open",
+        Message::Opened(_) => "opened session",
+        Message::Save => "save",
+        Message::Saved(_) => "save result",
+        Message::TogglePopulation => "population toggle",
+        Message::PopulationSimulated(_) => "population",
+        Message::CheckScripts => "script check",
+    };
+    eprintln!(
+        "warning: ignored a stale {} while no results are shown",
+        kind
+    );
+    Task::none()
+}
+
+async fn export(data: Data, include_history: bool) -> Result<Vec<PathBuf>, String> {
+    export_tournament(
+        std::path::Path::new(EXPORT_DIR),
+        &data.config,
+        &data.strategy_names,
+        &data.matchup_results,
+        &data.stats,
+        include_history,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn load(config: TournamentConfig) -> Result<Data, String> {
+    let config = config.with_resolved_seed();
+    // taken before the strategies are loaded, so a script saved while the tournament runs triggers another run
+    let scripts = scripted::fingerprint(Path::new(SCRIPT_DIR));
+    registry::reload();
+    let strategies = config.resolve_strategies()?;
+
+    let strategy_names = strategies.iter().map(|(name, _)| name.clone()).collect();
+    let grid_width = strategies.len();
+    let matchup_results = run_competition(strategies, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let stats = calculate_stats(&matchup_results, &config, grid_width).await;
+
+    Ok(Data {
+        config,
+        strategy_names,
+        matchup_results,
+        stats,
+        scripts: Some(scripts),
+        strategy_errors: registry::errors(),
+    })
+}
+
 async fn calculate_cell_and_strategy_colors(stat: Arc<Stat>) -> Colors {
-    let average = stat.strategy_averages.iter().sum::<f64>() / stat.strategy_averages.len() as f64;
+    let average = stat.overall_average();
 
     let (cell_colors, strategy_colors) = tokio::join!(
         calculate_colors(average, &stat.values, Color::BLACK),
         calculate_colors(average, &stat.strategy_averages, crate::colors::LIGHT_GRAY)
     );
 
+    // cells whose confidence interval contains the average are washed out
+    let cell_colors = cell_colors
+        .into_iter()
+        .enumerate()
+        .map(|(i, color)| {
+            if stat.is_significant(i, average) {
+                color
+            } else {
+                blend_colors(color, crate::colors::LIGHT_GRAY, INSIGNIFICANT_FADE)
+            }
+        })
+        .collect();
+
     Colors {
         cell_colors,
         strategy_colors,
     }
 }
 
-async fn calculate_colors(standard: f64, values: &Vec<f64>, default: Color) -> Vec<Color> {
+pub(super) async fn calculate_colors(
+    standard: f64,
+    values: &Vec<f64>,
+    default: Color,
+) -> Vec<Color> {
     let deviance = values.par_iter().map(|v| v - standard).collect::<Vec<_>>();
 
-    let max_deviance = deviance
+    // without a finite deviance (no scores, or only NaN ones) there is nothing to scale by
+    let Some(max_deviance) = deviance
         .par_iter()
+        .filter(|f| f.is_finite())
         .map(|f| f.abs())
         .max_by(|a, b| a.total_cmp(b))
-        .expect("No maximum found, is the Vec empty?");
+    else {
+        return vec![default; values.len()];
+    };
 
     let deviance_percents = deviance
         .par_iter()
@@ -397,6 +841,10 @@ async fn calculate_colors(standard: f64, values: &Vec<f64>, default: Color) -> V
 }
 
 fn calculate_color(deviation_percent: f32, default: Color) -> Color {
+    if !deviation_percent.is_finite() {
+        return default;
+    }
+
     let to_blend_with = if deviation_percent > 0.0 {
         crate::colors::BLUE
     } else {
@@ -22,10 +22,7 @@ impl<'a> Grid {
             num_cols,
             num_rows,
             mark_diagonals,
-            cells: (0..(num_cols * num_rows))
-                .into_iter()
-                .map(|i| Cell::new(i))
-                .collect(),
+            cells: (0..(num_cols * num_rows)).map(Cell::new).collect(),
         }
     }
 
@@ -51,7 +48,7 @@ impl<'a> Grid {
         x * self.num_cols + y
     }
 
-    pub fn view(&self, colors: &Vec<Color>, cell_size: u16) -> Element<GridMessage> {
+    pub fn view(&self, colors: &[Color], cell_size: u16) -> Element<'_, GridMessage> {
         let rows = self
             .cells
             .chunks(self.num_cols)
@@ -70,12 +67,15 @@ impl<'a> Grid {
         row_cells: &'a [Cell],
         row_colors: &[Color],
         cell_size: u16,
-    ) -> Element<GridMessage> {
-        let cells = row_cells
-            .iter()
-            .zip(row_colors)
-            .enumerate()
-            .map(|(col_index, (cell, &color))| self.view_cell(row_index, col_index, cell, color, cell_size));
+    ) -> Element<'a, GridMessage> {
+        let cells =
+            row_cells
+                .iter()
+                .zip(row_colors)
+                .enumerate()
+                .map(|(col_index, (cell, &color))| {
+                    self.view_cell(row_index, col_index, cell, color, cell_size)
+                });
 
         row(cells).into()
     }
@@ -87,18 +87,19 @@ impl<'a> Grid {
         cell: &'a Cell,
         color: Color,
         cell_size: u16,
-    ) -> Element<GridMessage> {
-        cell.view(color, self.mark_diagonals && row == col, cell_size).map(move |m| match m {
-            CellMessage::Focus => GridMessage::Focus(row, col),
-            CellMessage::Unfocus => GridMessage::Unfocus(row, col),
-            CellMessage::ToggleFocus => {
-                if cell.is_selected {
-                    GridMessage::Unfocus(row, col)
-                } else {
-                    GridMessage::Focus(row, col)
+    ) -> Element<'a, GridMessage> {
+        cell.view(color, self.mark_diagonals && row == col, cell_size)
+            .map(move |m| match m {
+                CellMessage::Focus => GridMessage::Focus(row, col),
+                CellMessage::Unfocus => GridMessage::Unfocus(row, col),
+                CellMessage::ToggleFocus => {
+                    if cell.is_selected {
+                        GridMessage::Unfocus(row, col)
+                    } else {
+                        GridMessage::Focus(row, col)
+                    }
                 }
-            }
-        })
+            })
     }
 }
 
@@ -131,7 +132,7 @@ impl Cell {
         };
     }
 
-    pub fn view(&self, color: Color, show_border: bool, size: u16) -> Element<CellMessage> {
+    pub fn view(&self, color: Color, show_border: bool, size: u16) -> Element<'_, CellMessage> {
         container(
             button(Space::new(Length::Fill, Length::Fill))
                 .on_press(CellMessage::ToggleFocus)
@@ -1,4 +1,7 @@
+use iced::widget::button;
+use iced::widget::canvas;
 use iced::widget::column;
+use iced::widget::row;
 use iced::widget::scrollable;
 use iced::widget::text;
 use iced::Alignment;
@@ -6,31 +9,151 @@ use iced::Color;
 use iced::Element;
 use iced::Length;
 
-use crate::{MatchupResult, NUM_ROUNDS};
+use crate::{
+    play_round, stats::summarize, tournament::TournamentConfig, GameHistory, GameMove, GameResult,
+    MatchupResult,
+};
 
 use super::grid::Grid;
 use super::grid::GridMessage;
+use super::plot::LinePlot;
+
+/// Rounds averaged into every point of the reward curves
+const REWARD_WINDOW: usize = 25;
 
 #[derive(Debug, Clone)]
 pub enum MatchInspectorMessage {
     GridMessage(GridMessage),
+    /// Asks the parent to play this matchup again with the same seed
+    Replay,
+    /// History of the replayed matchup, compared with the stored one while that matchup is still inspected
+    Replayed(ReplayedMatchup, Result<GameHistory, String>),
+    /// Switches the grid between the stored and the replayed history
+    ToggleReplay,
+    SelectHistory(HistoryView),
+    /// Switches between the moves and the points per round of both players
+    ToggleRewards,
+}
+
+/// The stored history a replay reproduces, so that a replay finishing after another matchup was selected is dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayedMatchup {
+    pub first_name: String,
+    pub second_name: String,
+    pub repetition: usize,
+}
+
+impl ReplayedMatchup {
+    /// `MatchupResult::history` is the first repetition
+    pub fn of(matchup_result: &MatchupResult) -> Self {
+        ReplayedMatchup {
+            first_name: matchup_result.first_name.clone(),
+            second_name: matchup_result.second_name.clone(),
+            repetition: 0,
+        }
+    }
+}
+
+/// Which moves of a noisy match are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryView {
+    Intended,
+    #[default]
+    Executed,
+    Perceived,
+}
+
+impl HistoryView {
+    const ALL: [HistoryView; 3] = [
+        HistoryView::Intended,
+        HistoryView::Executed,
+        HistoryView::Perceived,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            HistoryView::Intended => "Intended",
+            HistoryView::Executed => "Executed",
+            HistoryView::Perceived => "Perceived",
+        }
+    }
 }
 
 pub struct MatchInspector {
     grid: Grid,
+    /// Stored history of the inspected matchup, the grid is resized when switching to a replay of another length
+    history: GameHistory,
+    /// Matchup of `history`, `None` when no matchup is selected
+    matchup: Option<ReplayedMatchup>,
+    replay: Option<Result<GameHistory, String>>,
+    show_replay: bool,
+    history_view: HistoryView,
+    show_rewards: bool,
+    /// Moving average of the points of both players, one entry per round
+    rewards: Vec<Vec<f64>>,
+    reward_colors: Vec<Color>,
 }
 
 impl Default for MatchInspector {
     fn default() -> Self {
         Self {
-            grid: Grid::new(2, NUM_ROUNDS, false),
+            grid: Grid::new(2, 0, false),
+            history: vec![],
+            matchup: None,
+            replay: None,
+            show_replay: false,
+            history_view: HistoryView::default(),
+            show_rewards: false,
+            rewards: vec![],
+            reward_colors: crate::colors::strategy_palette(2),
         }
     }
 }
 
 impl MatchInspector {
-    pub fn update(&mut self, _message: MatchInspectorMessage) {
-        // could be left blank tbh
+    pub fn update(&mut self, message: MatchInspectorMessage) {
+        match message {
+            MatchInspectorMessage::GridMessage(_) => {} // could be left blank tbh
+            MatchInspectorMessage::Replay => {
+                self.replay = None;
+                self.set_show_replay(false);
+            }
+            MatchInspectorMessage::Replayed(matchup, result) => {
+                if self.matchup.as_ref() == Some(&matchup) {
+                    self.replay = Some(result);
+                } else {
+                    eprintln!(
+                        "warning: dropped a replay of {} against {}, another matchup is inspected",
+                        matchup.first_name, matchup.second_name
+                    );
+                }
+            }
+            MatchInspectorMessage::ToggleReplay => self.set_show_replay(!self.show_replay),
+            MatchInspectorMessage::SelectHistory(view) => self.history_view = view,
+            MatchInspectorMessage::ToggleRewards => self.show_rewards = !self.show_rewards,
+        }
+    }
+
+    /// Prepares the inspector for another matchup, `None` when no matchup is selected
+    pub fn inspect(&mut self, matchup_result: Option<&MatchupResult>, config: &TournamentConfig) {
+        let history = matchup_result.map_or(&[][..], |m| &m.history);
+
+        self.grid = Grid::new(2, history.len(), false);
+        self.history = history.to_vec();
+        self.matchup = matchup_result.map(ReplayedMatchup::of);
+        self.replay = None;
+        self.show_replay = false;
+        self.rewards = reward_curves(history, config);
+    }
+
+    fn set_show_replay(&mut self, show_replay: bool) {
+        let rounds = match (&self.replay, show_replay) {
+            (Some(Ok(replayed)), true) => replayed.len(),
+            _ => self.history.len(),
+        };
+
+        self.show_replay = show_replay;
+        self.grid = Grid::new(2, rounds, false);
     }
 
     pub fn view(
@@ -38,42 +161,141 @@ impl MatchInspector {
         matchup_result: &MatchupResult,
         _round_index: Option<usize>,
         cell_size: u16,
-    ) -> Element<MatchInspectorMessage> {
+    ) -> Element<'_, MatchInspectorMessage> {
         let MatchupResult {
             first_name,
             second_name,
             overall_result,
             history,
+            noise,
+            results,
+            ..
         } = matchup_result;
 
-        let colors = history
+        let shown = match (&self.replay, self.show_replay, noise, self.history_view) {
+            (Some(Ok(replayed)), true, _, _) => replayed,
+            (_, _, Some(noise), HistoryView::Intended) => &noise.intended,
+            (_, _, Some(noise), HistoryView::Perceived) => &noise.perceived,
+            _ => history,
+        };
+
+        let colors: Vec<_> = shown
             .iter()
             .flat_map(|m| [m.0, m.1])
             .map(Self::calculate_move_color)
             .collect();
 
+        let first_score = format_score(overall_result.0, results, |r| r.0);
+        let second_score = format_score(overall_result.1, results, |r| r.1);
+
         let title = column!(
-            text!("{} - {:.2}", first_name, overall_result.0,).size(36),
-            text!("vs").size(18),
-            text!("{} - {:.2}", second_name, overall_result.1,).size(36)
+            text!("{} - {}", first_name, first_score).size(36),
+            text!("vs ({} rounds)", history.len()).size(18),
+            text!("{} - {}", second_name, second_score).size(36)
         )
         .align_x(Alignment::Center);
 
-        let visualization = column!(
-            self.grid
-                .view(&colors, cell_size)
-                .map(MatchInspectorMessage::GridMessage)
-        ,)
+        let difference = match &self.replay {
+            Some(Ok(replayed)) => first_difference(history, replayed),
+            _ => None,
+        };
+        let replay_status = match (&self.replay, difference) {
+            (None, _) => String::new(),
+            (Some(Ok(_)), None) => String::from("Replay is identical"),
+            (Some(Ok(replayed)), Some(round)) => describe_difference(history, replayed, round),
+            (Some(Err(e)), _) => format!("Replay failed: {}", e),
+        };
+
+        let mut controls = row!(
+            button(text("Replay")).on_press(MatchInspectorMessage::Replay),
+            button(text(if self.show_rewards {
+                "Moves"
+            } else {
+                "Rewards"
+            }))
+            .on_press(MatchInspectorMessage::ToggleRewards),
+        )
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        if difference.is_some() && !self.show_rewards {
+            controls = controls.push(
+                button(text(if self.show_replay {
+                    "Show stored"
+                } else {
+                    "Show replay"
+                }))
+                .on_press(MatchInspectorMessage::ToggleReplay),
+            );
+        }
+        controls = controls.push(text(replay_status));
+
+        // the three histories only differ when the engine added noise
+        if noise.is_some() && !self.show_rewards && !self.show_replay {
+            controls = HistoryView::ALL
+                .into_iter()
+                .fold(controls, |controls, view| {
+                    let select = button(text(view.label())).on_press_maybe(
+                        (view != self.history_view)
+                            .then_some(MatchInspectorMessage::SelectHistory(view)),
+                    );
+                    controls.push(select)
+                });
+        }
+
+        if self.show_rewards {
+            return self.view_rewards(title, controls, first_name, second_name);
+        }
+
+        let visualization = column!(self
+            .grid
+            .view(&colors, cell_size)
+            .map(MatchInspectorMessage::GridMessage),)
         .width(Length::Fill)
         .align_x(Alignment::Center);
 
-        let content = column!(title, scrollable(visualization).width(Length::Fill))
-            .align_x(Alignment::Center)
-            .width(Length::Fill);
+        let content = column!(
+            title,
+            controls,
+            scrollable(visualization).width(Length::Fill)
+        )
+        .align_x(Alignment::Center)
+        .width(Length::Fill);
 
         content.padding(4).into()
     }
 
+    /// Learning curves: the points per round of both players over the match, smoothed over `REWARD_WINDOW` rounds
+    fn view_rewards<'a>(
+        &'a self,
+        title: iced::widget::Column<'a, MatchInspectorMessage>,
+        controls: iced::widget::Row<'a, MatchInspectorMessage>,
+        first_name: &str,
+        second_name: &str,
+    ) -> Element<'a, MatchInspectorMessage> {
+        let legend = row!(
+            text(first_name.to_string()).color(self.reward_colors[0]),
+            text(second_name.to_string()).color(self.reward_colors[1]),
+            text!("points per round, averaged over {} rounds", REWARD_WINDOW),
+        )
+        .spacing(12);
+
+        let plot = canvas(LinePlot {
+            values: &self.rewards,
+            colors: &self.reward_colors,
+            highlighted: vec![],
+        })
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        column!(title, controls, legend, plot)
+            .align_x(Alignment::Center)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(4)
+            .into()
+    }
+
     fn calculate_move_color(mv: f64) -> Color {
         let a = (mv as f32 - 0.5) * 2.0;
 
@@ -85,3 +307,61 @@ impl MatchInspector {
         crate::colors::blend_colors(crate::colors::YELLOW, to_blend_with, a.abs())
     }
 }
+
+/// The mean score, followed by its standard deviation when the matchup was repeated
+fn format_score(mean: f64, results: &[GameResult], side: fn(&GameResult) -> f64) -> String {
+    if results.len() < 2 {
+        return format!("{:.2}", mean);
+    }
+
+    let samples: Vec<_> = results.iter().map(side).collect();
+    let (_, std_dev, _) = summarize(&samples);
+    format!("{:.2} ± {:.2}", mean, std_dev)
+}
+
+/// First round where the replayed history differs from the stored one, `None` when they are identical
+fn first_difference(stored: &[GameMove], replayed: &[GameMove]) -> Option<usize> {
+    stored
+        .iter()
+        .zip(replayed)
+        .position(|(a, b)| a != b)
+        .or_else(|| (stored.len() != replayed.len()).then_some(stored.len().min(replayed.len())))
+}
+
+fn describe_difference(stored: &[GameMove], replayed: &[GameMove], round: usize) -> String {
+    match (stored.get(round), replayed.get(round)) {
+        (Some(GameMove(x, y)), Some(GameMove(rx, ry))) => format!(
+            "Replay differs from round {}: ({:.3}, {:.3}) instead of ({:.3}, {:.3})",
+            round + 1,
+            rx,
+            ry,
+            x,
+            y
+        ),
+        _ => format!(
+            "Replay differs from round {}: {} rounds instead of {}",
+            round + 1,
+            replayed.len(),
+            stored.len()
+        ),
+    }
+}
+
+/// Trailing moving average of the points both players got in every round
+fn reward_curves(history: &[GameMove], config: &TournamentConfig) -> Vec<Vec<f64>> {
+    let points: Vec<_> = history
+        .iter()
+        .map(|GameMove(x, y)| play_round(*x, *y, &config.payoff, config.interpolation.as_ref()))
+        .collect();
+
+    (0..points.len())
+        .map(|round| {
+            let window = &points[round.saturating_sub(REWARD_WINDOW - 1)..=round];
+            let n = window.len() as f64;
+            vec![
+                window.iter().map(|r| r.0).sum::<f64>() / n,
+                window.iter().map(|r| r.1).sum::<f64>() / n,
+            ]
+        })
+        .collect()
+}
@@ -30,18 +30,36 @@ impl LabelList {
         };
     }
 
-    pub fn view(
-        &self,
-        labels: &Vec<&'static str>,
-        label_colors: &Vec<Color>,
+    /// Indices of the labels that are currently focused, in ascending order
+    pub fn selected(&self) -> Vec<usize> {
+        let mut selected: Vec<_> = self.selected_indicies.iter().copied().collect();
+        selected.sort_unstable();
+        selected
+    }
+
+    /// `swatches` are the strategy colors used by plots, shown as a small square next to each label
+    pub fn view<'a>(
+        &'a self,
+        labels: &'a [String],
+        label_colors: &[Color],
+        swatches: &[Color],
         cell_size: u16,
         align_x: Alignment,
-    ) -> Element<LabelListMessage> {
+    ) -> Element<'a, LabelListMessage> {
         let contents = labels
             .iter()
             .zip(label_colors)
+            .zip(swatches)
             .enumerate()
-            .map(|(index, (&label, &color))| self.view_label(index, label, color, cell_size));
+            .map(|(index, ((label, &color), &swatch))| {
+                row![
+                    self.view_label(index, label, color, cell_size),
+                    Self::view_swatch(swatch, cell_size)
+                ]
+                .spacing(Self::SPACING)
+                .align_y(Alignment::Center)
+                .into()
+            });
 
         column(contents)
             .align_x(align_x)
@@ -50,13 +68,13 @@ impl LabelList {
             .into()
     }
 
-    fn view_label(
-        &self,
+    fn view_label<'a>(
+        &'a self,
         index: usize,
-        label: &'static str,
+        label: &'a str,
         color: Color,
         cell_size: u16,
-    ) -> Element<LabelListMessage> {
+    ) -> Element<'a, LabelListMessage> {
         let is_selected = self.selected_indicies.contains(&index);
         let on_press_message = if is_selected {
             LabelListMessage::Unfocus(index)
@@ -74,10 +92,10 @@ impl LabelList {
             use button::{Status, Style};
 
             let tint = match status {
-                    Status::Pressed | Status::Hovered => 0.5, 
-                    Status::Active | Status::Disabled => 0.0,
-                };
-            
+                Status::Pressed | Status::Hovered => 0.5,
+                Status::Active | Status::Disabled => 0.0,
+            };
+
             Style {
                 background: None,
                 text_color: crate::colors::blend_colors(color, Color::WHITE, tint),
@@ -88,4 +106,15 @@ impl LabelList {
         .on_press(on_press_message)
         .into()
     }
+
+    fn view_swatch<'a>(color: Color, cell_size: u16) -> Element<'a, LabelListMessage> {
+        let size = cell_size / 3;
+
+        container(Space::new(size, size))
+            .style(move |_| container::Style {
+                background: Some(color.into()),
+                ..Default::default()
+            })
+            .into()
+    }
 }
@@ -0,0 +1,93 @@
+use iced::{
+    mouse,
+    widget::canvas::{self, Frame, Geometry, Path, Stroke},
+    Color, Point, Rectangle, Renderer, Theme,
+};
+
+/// Line chart of one value per line over time, such as population shares over the generations
+/// Values are drawn between the smaller of 0 and the lowest value, and the highest value
+#[derive(Debug)]
+pub struct LinePlot<'a> {
+    /// One entry per step, holding the value of every line
+    pub values: &'a [Vec<f64>],
+    /// Color of every line
+    pub colors: &'a [Color],
+    /// Lines that are drawn thicker, the rest is dimmed
+    pub highlighted: Vec<usize>,
+}
+
+impl LinePlot<'_> {
+    const PADDING: f32 = 8.0;
+}
+
+impl<Message> canvas::Program<Message> for LinePlot<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let width = bounds.width - Self::PADDING * 2.0;
+        let height = bounds.height - Self::PADDING * 2.0;
+
+        let axes = Path::new(|b| {
+            b.move_to(Point::new(Self::PADDING, Self::PADDING));
+            b.line_to(Point::new(Self::PADDING, Self::PADDING + height));
+            b.line_to(Point::new(Self::PADDING + width, Self::PADDING + height));
+        });
+        frame.stroke(
+            &axes,
+            Stroke::default()
+                .with_color(crate::colors::LIGHT_GRAY)
+                .with_width(1.0),
+        );
+
+        let steps = self.values.len();
+        let finite = || {
+            self.values
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|v| v.is_finite())
+        };
+        let highest = finite().fold(0.0, f64::max);
+        let lowest = finite().fold(0.0, f64::min);
+
+        if steps < 2 || highest <= lowest {
+            return vec![frame.into_geometry()];
+        }
+
+        let to_point = |step: usize, value: f64| {
+            Point::new(
+                Self::PADDING + width * step as f32 / (steps - 1) as f32,
+                Self::PADDING + height * (1.0 - ((value - lowest) / (highest - lowest)) as f32),
+            )
+        };
+
+        for (line, &color) in self.colors.iter().enumerate() {
+            let path = Path::new(|b| {
+                b.move_to(to_point(0, self.values[0][line]));
+                for (step, values) in self.values.iter().enumerate().skip(1) {
+                    b.line_to(to_point(step, values[line]));
+                }
+            });
+
+            let is_highlighted = self.highlighted.contains(&line);
+            let (color, width) = match (self.highlighted.is_empty(), is_highlighted) {
+                (true, _) => (color, 1.5),
+                (false, true) => (color, 3.0),
+                (false, false) => (Color { a: 0.25, ..color }, 1.0),
+            };
+
+            frame.stroke(&path, Stroke::default().with_color(color).with_width(width));
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use iced::{
+    time,
+    widget::{button, column, row, scrollable, text},
+    Alignment, Color, Element, Length, Subscription, Task,
+};
+
+use crate::{
+    spatial::{Lattice, SpatialConfig},
+    tournament::TournamentConfig,
+    NamedStrategy,
+};
+
+use super::{
+    grid::{Grid, GridMessage},
+    labels::{LabelList, LabelListMessage},
+};
+
+/// Delay between generations while playing
+const TICK: Duration = Duration::from_millis(250);
+
+/// Window showing a spatial tournament generation by generation
+pub struct SpatialViewer {
+    strategies: Vec<NamedStrategy>,
+    config: TournamentConfig,
+    spatial: SpatialConfig,
+
+    lattice: Lattice,
+    /// Strategy names followed by the number of sites they hold
+    labels: Vec<String>,
+    palette: Vec<Color>,
+    cell_size: u16,
+
+    grid: Grid,
+    label_list: LabelList,
+    selected_site: Option<usize>,
+
+    playing: bool,
+    /// Whether a generation is being computed, ticks are skipped until it is done
+    stepping: bool,
+    /// Bumped by every reset, so that a generation computed before the reset is dropped
+    resets: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum SpatialMessage {
+    TogglePlay,
+    Step,
+    Stepped(usize, Lattice),
+    Reset,
+    GridMessage(GridMessage),
+    LabelListMessage(LabelListMessage),
+}
+
+impl SpatialViewer {
+    pub fn new(
+        strategies: Vec<NamedStrategy>,
+        config: TournamentConfig,
+        spatial: SpatialConfig,
+    ) -> (SpatialViewer, Task<SpatialMessage>) {
+        let lattice = Lattice::random(&spatial, strategies.len(), &config);
+        let cell_size = (720 / spatial.width.max(spatial.height)).clamp(4, 30) as u16;
+
+        let mut viewer = SpatialViewer {
+            palette: crate::colors::strategy_palette(strategies.len()),
+            grid: Grid::new(spatial.width, spatial.height, false),
+            label_list: LabelList::new(),
+            labels: vec![],
+            selected_site: None,
+            playing: false,
+            stepping: false,
+            resets: 0,
+            cell_size,
+            strategies,
+            config,
+            spatial,
+            lattice,
+        };
+        viewer.refresh_labels();
+
+        (viewer, Task::none())
+    }
+
+    pub fn update(&mut self, message: SpatialMessage) -> Task<SpatialMessage> {
+        match message {
+            SpatialMessage::TogglePlay => {
+                self.playing = !self.playing;
+                Task::none()
+            }
+            SpatialMessage::Step => {
+                if self.stepping {
+                    return Task::none();
+                }
+                self.stepping = true;
+
+                let resets = self.resets;
+                Task::perform(
+                    step(
+                        self.lattice.clone(),
+                        self.strategies.clone(),
+                        self.config.clone(),
+                        self.spatial.clone(),
+                    ),
+                    move |lattice| SpatialMessage::Stepped(resets, lattice),
+                )
+            }
+            SpatialMessage::Stepped(resets, lattice) => {
+                self.stepping = false;
+                if resets == self.resets {
+                    self.lattice = lattice;
+                    self.refresh_labels();
+                }
+                Task::none()
+            }
+            SpatialMessage::Reset => {
+                self.playing = false;
+                self.resets += 1;
+                self.lattice = Lattice::random(&self.spatial, self.strategies.len(), &self.config);
+                self.refresh_labels();
+                Task::none()
+            }
+            SpatialMessage::GridMessage(grid_message) => {
+                match grid_message {
+                    GridMessage::Focus(row, col) => {
+                        if let Some(previous) = self.selected_site {
+                            let width = self.lattice.width;
+                            self.grid
+                                .update(GridMessage::Unfocus(previous / width, previous % width));
+                        }
+                        self.selected_site = Some(row * self.lattice.width + col);
+                    }
+                    GridMessage::Unfocus(..) => self.selected_site = None,
+                }
+
+                self.grid.update(grid_message);
+                Task::none()
+            }
+            SpatialMessage::LabelListMessage(message) => {
+                self.label_list.update(message);
+                Task::none()
+            }
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<SpatialMessage> {
+        if self.playing {
+            time::every(TICK).map(|_| SpatialMessage::Step)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, SpatialMessage> {
+        let title = text!("Generation {}", self.lattice.generation).size(36);
+
+        let selected = self.selected_site.map_or(String::new(), |site| {
+            format!(
+                "({}, {}): {}",
+                site / self.lattice.width,
+                site % self.lattice.width,
+                self.strategies[self.lattice.sites[site]].0
+            )
+        });
+
+        let controls = row!(
+            button(text(if self.playing { "Pause" } else { "Play" }))
+                .on_press(SpatialMessage::TogglePlay),
+            button(text("Step")).on_press_maybe((!self.playing).then_some(SpatialMessage::Step)),
+            button(text("Reset")).on_press(SpatialMessage::Reset),
+            text(selected),
+        )
+        .spacing(12)
+        .align_y(Alignment::Center);
+
+        // focused labels are drawn normally, every other strategy is dimmed
+        let highlighted = self.label_list.selected();
+        let colors: Vec<_> = self
+            .lattice
+            .sites
+            .iter()
+            .map(|&s| {
+                let color = self.palette[s];
+                if highlighted.is_empty() || highlighted.contains(&s) {
+                    color
+                } else {
+                    crate::colors::blend_colors(color, Color::BLACK, 0.8)
+                }
+            })
+            .collect();
+
+        let content = row!(
+            scrollable(
+                self.label_list
+                    .view(
+                        &self.labels,
+                        &self.palette,
+                        &self.palette,
+                        20,
+                        Alignment::End
+                    )
+                    .map(SpatialMessage::LabelListMessage)
+            ),
+            self.grid
+                .view(&colors, self.cell_size)
+                .map(SpatialMessage::GridMessage),
+        )
+        .height(Length::Fill)
+        .align_y(Alignment::Center)
+        .spacing(6)
+        .padding(4);
+
+        column!(title, controls, content)
+            .align_x(Alignment::Center)
+            .spacing(6)
+            .padding(4)
+            .into()
+    }
+
+    fn refresh_labels(&mut self) {
+        let counts = self.lattice.counts(self.strategies.len());
+        self.labels = self
+            .strategies
+            .iter()
+            .zip(counts)
+            .map(|((name, _), count)| format!("{} ({})", name, count))
+            .collect();
+    }
+}
+
+/// Plays a generation on the blocking pool, as its matches would otherwise hold up the executor running the window
+async fn step(
+    lattice: Lattice,
+    strategies: Vec<NamedStrategy>,
+    config: TournamentConfig,
+    spatial: SpatialConfig,
+) -> Lattice {
+    tokio::task::spawn_blocking(move || lattice.step(&strategies, &config, &spatial))
+        .await
+        .expect("Spatial step panicked")
+}
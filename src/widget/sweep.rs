@@ -0,0 +1,139 @@
+use iced::{
+    widget::{column, row, text},
+    Alignment, Color, Element, Length, Task,
+};
+
+use crate::{
+    sweep::{run_sweep, SweepConfig, SweepResult},
+    tournament::TournamentConfig,
+    NamedStrategy,
+};
+
+use super::{
+    app::calculate_colors,
+    grid::{Grid, GridMessage},
+};
+
+/// Window showing the score of every `MemoryOne` variant of a parameter sweep as a heatmap
+pub struct SweepViewer {
+    sweep: SweepConfig,
+    result: Option<Result<(SweepResult, Vec<Color>), String>>,
+    cell_size: u16,
+
+    grid: Grid,
+    selected_cell: Option<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SweepMessage {
+    Finished(Result<(SweepResult, Vec<Color>), String>),
+    GridMessage(GridMessage),
+}
+
+impl SweepViewer {
+    pub fn new(
+        strategies: Vec<NamedStrategy>,
+        config: TournamentConfig,
+        sweep: SweepConfig,
+    ) -> (SweepViewer, Task<SweepMessage>) {
+        let viewer = SweepViewer {
+            grid: Grid::new(sweep.steps, sweep.steps, false),
+            cell_size: (640 / sweep.steps).clamp(8, 48) as u16,
+            result: None,
+            selected_cell: None,
+            sweep: sweep.clone(),
+        };
+
+        (
+            viewer,
+            Task::perform(
+                sweep_with_colors(strategies, config, sweep),
+                SweepMessage::Finished,
+            ),
+        )
+    }
+
+    pub fn update(&mut self, message: SweepMessage) -> Task<SweepMessage> {
+        match message {
+            SweepMessage::Finished(result) => self.result = Some(result),
+            SweepMessage::GridMessage(grid_message) => {
+                match grid_message {
+                    GridMessage::Focus(row, col) => {
+                        if let Some((previous_row, previous_col)) = self.selected_cell {
+                            self.grid
+                                .update(GridMessage::Unfocus(previous_row, previous_col));
+                        }
+                        self.selected_cell = Some((row, col));
+                    }
+                    GridMessage::Unfocus(..) => self.selected_cell = None,
+                }
+
+                self.grid.update(grid_message);
+            }
+        }
+        Task::none()
+    }
+
+    pub fn view(&self) -> Element<'_, SweepMessage> {
+        let (result, colors) = match &self.result {
+            None => return text("Running sweep...").size(24).into(),
+            Some(Err(e)) => return text!("Sweep failed: {}", e).size(24).into(),
+            Some(Ok((result, colors))) => (result, colors),
+        };
+
+        let (x, y) = (self.sweep.x.name(), self.sweep.y.name());
+        let title = column!(
+            text!("{} vs {}", x, y).size(36),
+            text!(
+                "columns: {} from 0 to 1, rows: {} from 0 to 1, points per round against the entered strategies",
+                x,
+                y
+            )
+            .size(18)
+        )
+        .align_x(Alignment::Center);
+
+        let describe = |(row, col): (usize, usize)| {
+            format!(
+                "{} = {:.2}, {} = {:.2}: {:.4}",
+                x,
+                result.x_values[col],
+                y,
+                result.y_values[row],
+                result.score(row, col)
+            )
+        };
+        let best = result
+            .best()
+            .map_or(String::new(), |cell| format!("Best: {}", describe(cell)));
+        let selected = self.selected_cell.map_or(String::new(), describe);
+
+        let details = row!(text(best), text(selected)).spacing(24);
+
+        let heatmap = self
+            .grid
+            .view(colors, self.cell_size)
+            .map(SweepMessage::GridMessage);
+
+        column!(title, details, heatmap)
+            .align_x(Alignment::Center)
+            .width(Length::Fill)
+            .spacing(6)
+            .padding(4)
+            .into()
+    }
+}
+
+/// Runs the sweep and colors every variant by how far it scored from the average variant
+async fn sweep_with_colors(
+    strategies: Vec<NamedStrategy>,
+    config: TournamentConfig,
+    sweep: SweepConfig,
+) -> Result<(SweepResult, Vec<Color>), String> {
+    let result = run_sweep(strategies, &config, &sweep).await?;
+
+    let average = result.scores.iter().sum::<f64>() / result.scores.len() as f64;
+    let colors = calculate_colors(average, &result.scores, Color::BLACK).await;
+
+    Ok((result, colors))
+}